@@ -0,0 +1,269 @@
+# Deutscher Katalog; fehlende Nachrichten kommen aus en.ftl.
+# Die Befehle für Mitglieder (/watch, /stats) haben deutsche Namen, /configure behält seine Namen wie in der README.
+
+language-name = Deutsch
+
+## Slash-Befehle
+
+cmd-amazon = Bereinige und tagge deinen Amazon- oder eBay-Link
+cmd-amazon-url = Dein Amazon- oder eBay-Link, z. B. https://amzn.to/...
+cmd-amazon-region = Stattdessen den Link für diesen Amazon-Marktplatz (nur Produktlinks)
+
+cmd-stats = Statistiken zu erzeugten Links anzeigen
+    .name = statistik
+
+cmd-watch = Benachrichtigung, sobald ein Amazon-Produkt günstiger wird
+    .name = preisalarm
+cmd-watch-add = Den Preis eines Amazon-Produkts beobachten
+    .name = hinzufügen
+cmd-watch-add-link = Link zum Amazon-Produkt
+cmd-watch-add-target-price = Alarm unter diesem Preis (Standard: unter dem aktuellen Preis)
+    .name = zielpreis
+cmd-watch-list = Deine Preisalarme anzeigen
+    .name = liste
+cmd-watch-remove = Ein Produkt nicht mehr beobachten
+    .name = entfernen
+cmd-watch-remove-id = ID aus /preisalarm liste
+
+cmd-configure = 🌍 Affiliate-Tracking für Amazon-Marktplätze und andere Netzwerke einrichten
+cmd-configure-region = Tracking-Tag, Footer und behaltene URL-Parameter einer Region festlegen
+cmd-configure-region-region = Einzurichtende Amazon-Region
+cmd-configure-network = Tracking-ID für ein anderes Affiliate-Netzwerk festlegen (eBay, ...)
+cmd-configure-network-network = Einzurichtendes Affiliate-Netzwerk
+cmd-configure-network-tracking-id = Deine Tracking-ID, z. B. die EPN-Kampagnen-ID; leer lassen zum Entfernen
+cmd-configure-rule = Umschreibregeln für Shops ohne eingebaute Unterstützung
+cmd-configure-rule-add = Regel für eine Shop-Domain hinzufügen oder ersetzen
+cmd-configure-rule-add-host = Shop-Domain inklusive Subdomains, z. B. aliexpress.com
+cmd-configure-rule-add-strip = Zu entfernende Parameter, z. B. utm_source, aff_id (* entfernt alle)
+cmd-configure-rule-add-add = Hinzuzufügende Parameter, z. B. ref=unserserver
+cmd-configure-rule-list = Umschreibregeln dieses Servers anzeigen
+cmd-configure-rule-remove = Eine Umschreibregel entfernen
+cmd-configure-rule-remove-id = Regel-ID aus /configure rule list
+cmd-configure-rule-test = Zeigen, wie eine Beispiel-URL umgeschrieben würde
+cmd-configure-rule-test-url = Beispiel-URL, z. B. https://shop.example/item/1?utm_source=x
+cmd-configure-channels = Kanäle und Kategorien wählen, in denen Links verarbeitet werden
+cmd-configure-channels-allow = Links nur in erlaubten Kanälen und Kategorien verarbeiten
+cmd-configure-channels-deny = Links in diesem Kanal oder dieser Kategorie nie verarbeiten
+cmd-configure-channels-remove = Kanal oder Kategorie von den Listen entfernen
+cmd-configure-channels-list = Erlaubte und gesperrte Kanäle anzeigen
+cmd-configure-channels-channel = Kanal oder Kategorie; Threads folgen ihrem Kanal
+cmd-configure-roles = Rollen von der Verarbeitung ausnehmen oder nur bestimmte Rollen verarbeiten
+cmd-configure-roles-exempt = Links von Mitgliedern mit dieser Rolle nie verarbeiten
+cmd-configure-roles-only = Nur Links von Mitgliedern mit einer dieser Rollen verarbeiten
+cmd-configure-roles-remove = Rolle von den Listen entfernen
+cmd-configure-roles-list = Ausgenommene und verarbeitete Rollen anzeigen
+cmd-configure-roles-role = Rolle
+cmd-configure-link-mode = Festlegen, wie der Bot gepostete Links behandelt, serverweit oder pro Kanal
+cmd-configure-link-mode-mode = Wie der Bot auf gepostete Links reagiert
+cmd-configure-link-mode-channel = Nur diesen Kanal ändern (Standard: ganzer Server)
+cmd-configure-tag-policy = Links behandeln, die schon ein Affiliate-Tag tragen
+cmd-configure-tag-policy-policy = Was mit Links passiert, die schon ein fremdes Affiliate-Tag tragen
+cmd-configure-tag-policy-mod-channel = Kanal für gemeldete Nachrichten (nötig für „Melden“)
+cmd-configure-hint = Hinweis anpassen, der beim Löschen einer reinen Link-Nachricht erscheint
+cmd-configure-hint-text = Hinweistext; Platzhalter: {"{{"}sender{"}}"}, {"{{"}command{"}}"}, {"{{"}lifetime{"}}"}
+cmd-configure-hint-lifetime = Sekunden, bis der Hinweis wieder gelöscht wird (Standard: 10)
+cmd-configure-hint-reset = Zum eingebauten Hinweis und seiner Anzeigedauer zurückkehren
+cmd-configure-dedup = Dasselbe Produkt behandeln, wenn es in einem Kanal wiederholt gepostet wird
+cmd-configure-dedup-mode = Was passiert, wenn ein Produkt kurz nach der Antwort des Bots erneut gepostet wird
+cmd-configure-dedup-window = Minuten, die ein Produkt als beantwortet gilt (Standard: 10)
+cmd-configure-product-api = Eigene Product-Advertising-API-Schlüssel für Produkt-Embeds in einem Formular eingeben
+cmd-configure-product-api-remove = Deine Schlüssel entfernen und die des Bots nutzen, falls vorhanden
+cmd-configure-deals-channel = Preisalarme in einem Kanal statt per DM posten
+cmd-configure-deals-channel-channel = Kanal für Alarme (weglassen, um Alarme per DM zu senden)
+cmd-configure-also-offer = Buttons für dasselbe Produkt auf anderen Amazon-Marktplätzen hinzufügen
+cmd-configure-also-offer-marketplaces = Bis zu 4 Marktplätze, z. B. „com, co.uk“ („none“ zum Abschalten)
+cmd-configure-language = Sprache der Bot-Nachrichten auf diesem Server wählen
+cmd-configure-language-language = Sprache (automatisch: Discord-Sprache des jeweiligen Mitglieds)
+
+## Auswahlmöglichkeiten
+
+link-mode-delete-and-hint = Reine Link-Nachrichten mit Hinweis löschen (Standard)
+link-mode-button-reply = Mit Link-Buttons antworten
+link-mode-text-reply = Mit den bereinigten Links antworten
+link-mode-suppress-embeds-and-reply = Originalvorschauen ausblenden und mit den Links antworten
+link-mode-webhook-repost = Nachricht mit bereinigten Links neu posten (Webhook)
+link-mode-off = Aus, Links ignorieren
+link-mode-reset = Zurücksetzen (Kanal: Servermodus, Server: Standard)
+
+tag-policy-replace = Durch unser Tag ersetzen (Standard)
+tag-policy-keep = Fremdes Tag behalten, Link nicht anfassen
+tag-policy-reject = Nachricht ablehnen
+tag-policy-flag = Ersetzen und im Mod-Kanal melden
+
+dedup-mode-off = Jeden Post beantworten (Standard)
+dedup-mode-react = Mit 🔁 reagieren
+dedup-mode-jump-link = Mit Link zur früheren Antwort antworten
+dedup-mode-silent = Nichts tun
+
+language-auto = Automatisch (Discord-Sprache des jeweiligen Mitglieds)
+
+## Link-Antworten im Chat
+
+button-amazon-product = 🛒 Auf Amazon ansehen
+button-amazon-search = 🔍 Auf Amazon suchen
+button-amazon-store = 🏬 Shop besuchen
+button-amazon-wishlist = 🎁 Wunschliste ansehen
+button-amazon-idea-list = 💡 Ideenliste ansehen
+button-amazon-deals = 🔥 Angebote ansehen
+button-ebay-item = 🛒 Auf eBay ansehen
+button-ebay-search = 🔍 Auf eBay suchen
+button-open-link = 🔗 Link öffnen
+
+footer-default = Mit diesem Link unterstützt du unseren Server!
+footer-recommended = { $sender } empfiehlt das. { $footer }
+
+hint-default = { $sender }, bitte nutze { $command }, um deinen Link zu bereinigen und zu taggen.
+cooldown = ⏳ Nicht so schnell, Links werden in { $seconds ->
+    [one] einer Sekunde
+   *[other] { $seconds } Sekunden
+} wieder bereinigt.
+foreign-tag-rejected = { $sender }, Links mit fremden Affiliate-Tags sind hier nicht erlaubt.
+foreign-tag-report = 🚩 { $sender } hat in { $channel } einen Link mit einem fremden Affiliate-Tag gepostet: { $link }
+dedup-already-shared = 🔁 Wurde hier schon geteilt: { $link }
+
+product-ratings = { $count } Bewertungen
+product-disclaimer = Preis und Verfügbarkeit zum angegebenen Zeitpunkt, Änderungen vorbehalten
+
+## /amazon
+
+link-blocked = Dieser Link kann nicht aufgelöst werden. Nur Amazon- und eBay-Links werden unterstützt.
+link-timed-out = Der Shop hat zu lange gebraucht. Bitte versuche es gleich noch einmal.
+link-resolve-failed = Dieser Kurzlink konnte nicht aufgelöst werden. Versuche es erneut oder füge die vollständige URL ein.
+link-no-tag = Für diese Region ist kein Tracking-Tag verfügbar.
+link-unsupported = Dieser Link konnte nicht gelesen werden. Ist es ein gültiger Amazon- oder eBay-Link?
+link-not-convertible = Nur Amazon-Produktlinks können auf einen anderen Marktplatz umgestellt werden.
+
+## /statistik
+
+stats-error = ❌ Statistiken konnten nicht geladen werden. Bitte versuche es später erneut.
+stats-title = 📊 Affilify-Statistiken
+stats-description = Statistiken zu erzeugten Links auf diesem Server
+stats-global = 🌐 Insgesamt
+stats-server = 🏠 Dieser Server
+stats-top-regions = 📈 Top-Regionen
+stats-no-regions = Noch keine Regionen
+stats-links = { $count ->
+    [one] { $count } Link
+   *[other] { $count } Links
+}
+stats-clicks = { $count ->
+    [one] { $count } Klick in { $days } Tagen
+   *[other] { $count } Klicks in { $days } Tagen
+}
+stats-custom-rule = 🔧 **{ $host }** (eigene Regel)
+stats-footer = Teilt weiter fleißig Affiliate-Links! 💰
+
+## /preisalarm
+
+watch-removed = ✅ Preisalarm entfernt.
+watch-not-found = ❌ Kein Preisalarm mit dieser ID. Siehe `/preisalarm liste`.
+watch-remove-error = ❌ Fehler beim Entfernen des Preisalarms: { $error }
+watch-limit = ❌ Du kannst höchstens { $max } Produkte beobachten. Entferne eines mit `/preisalarm entfernen`.
+watch-resolve-failed = ❌ Dieser Kurzlink konnte nicht aufgelöst werden. Bitte füge die vollständige Produkt-URL ein.
+watch-not-a-product = ❌ Bitte nutze einen Link zu einer Amazon-Produktseite.
+watch-price-unknown = ❌ Der aktuelle Preis dieses Produkts ist unbekannt, bitte gib einen `zielpreis` an.
+watch-added = 👀 Beobachte `{ $asin }` auf { $domain } (ID { $id }). Du bekommst einen Alarm, sobald es unter **{ $target }** liegt.
+watch-added-with-price = 👀 Beobachte `{ $asin }` auf { $domain } (ID { $id }). Aktueller Preis: **{ $price }**. Du bekommst einen Alarm, sobald es unter **{ $target }** liegt.
+watch-save-error = ❌ Fehler beim Speichern des Preisalarms: { $error }
+watch-list-empty = Du beobachtest keine Produkte. Leg los mit `/preisalarm hinzufügen`.
+watch-list-title = **Deine Preisalarme:**
+watch-list-entry = `{ $id }` · <{ $url }> · unter **{ $target }**
+watch-alert = 📉 **Preissturz:** jetzt **{ $price }** (dein Ziel: unter { $target })
+    { $link }
+    -# Dieser Preisalarm ist beendet. Mit `/preisalarm hinzufügen` beobachtest du das Produkt erneut.
+
+## /configure
+
+configure-server-only = Dieser Befehl kann nur auf einem Server genutzt werden.
+configure-not-admin = Nur Administratoren oder der Serverbesitzer können diesen Befehl ausführen.
+configure-save-error = ❌ Fehler beim Speichern der Einstellungen: { $error }
+
+configure-unknown-region = ❌ Unbekannte oder nicht unterstützte Amazon-Region `{ $region }`. Bitte wähle eine aus der Liste.
+configure-global-region = 🌍 Globale Einstellungen (alle Regionen)
+configure-modal-title = { $flag } { $domain } einrichten
+configure-modal-title-global = 🌍 Globale Amazon-Einstellungen
+configure-modal-tag = 🏷️ Tracking-Tag für { $region }
+configure-modal-footer = 💬 Eigener Footer (optional)
+configure-modal-footer-placeholder = {"{{"}sender{"}}"} empfiehlt das und unterstützt unseren Server!
+configure-modal-params = 🔗 Behaltene URL-Parameter (serverweit)
+configure-updated-global = ✅ Globale Einstellungen aktualisiert!
+    🌍 { $count } Regionen eingerichtet
+configure-updated = ✅ Einstellungen für { $region } aktualisiert!
+    🌍 { $count } Einträge eingerichtet
+configure-no-changes = ℹ️ Keine Änderungen.
+
+configure-tracking-id-invalid = ❌ Tracking-IDs dürfen nur Buchstaben, Ziffern, `-` und `_` enthalten.
+configure-tracking-id-set = ✅ { $network }-Tracking-ID auf `{ $id }` gesetzt.
+configure-tracking-id-removed = ✅ { $network }-Tracking-ID entfernt, Links nutzen den Standard.
+
+configure-rule-saved = ✅ Regel `#{ $id }` für `{ $host }` gespeichert
+    • Entfernen: `{ $strip }`
+    • Hinzufügen: `{ $add }`
+configure-rule-save-error = ❌ Fehler beim Speichern der Regel: { $error }
+configure-rule-invalid = ❌ Ungültige Regel: { $error }.
+configure-rule-list-empty = ℹ️ Noch keine Umschreibregeln. Füge eine mit `/configure rule add` hinzu.
+configure-rule-entry = `#{ $id }` **{ $host }** — entfernt `{ $strip }`, fügt `{ $add }` hinzu
+configure-rule-removed = ✅ Regel entfernt.
+configure-rule-not-found = ❌ Keine Regel mit dieser ID. Siehe `/configure rule list`.
+configure-rule-remove-error = ❌ Fehler beim Entfernen der Regel: { $error }
+configure-rule-builtin = ℹ️ Dieser Link wird von der eingebauten Amazon-/eBay-Logik behandelt, Regeln gelten hier nicht.
+configure-rule-matches = 🔧 Regel `#{ $id }` ({ $host }) passt:
+    { $url }
+configure-rule-invalid-url = ❌ Das ist keine gültige URL.
+configure-rule-no-match = ℹ️ Keine Regel passt zu dieser URL, sie bliebe unverändert.
+
+configure-channels-list = ✅ **Erlaubt:** { $allowed }
+    ⛔ **Gesperrt:** { $denied }
+    -# Mit einer Erlaubt-Liste werden Links nur in erlaubten Kanälen verarbeitet. Der genaueste Eintrag gewinnt, Threads folgen ihrem Kanal.
+configure-not-listed = ℹ️ { $target } steht auf keiner Liste.
+configure-unlisted = ✅ { $target } von den Listen entfernt.
+configure-channel-allowed = ✅ Links werden in { $channel } verarbeitet.
+configure-channel-denied = ✅ Links werden in { $channel } nicht mehr verarbeitet.
+configure-roles-list = 🛡️ **Ausgenommen:** { $exempt }
+    ✅ **Nur diese Rollen:** { $only }
+    -# Ausgenommene Rollen gewinnen immer. Ohne „Nur“-Rollen werden alle anderen verarbeitet.
+configure-role-exempt = ✅ Links von Mitgliedern mit { $role } bleiben unverändert.
+configure-role-only = ✅ Links von Mitgliedern mit { $role } werden verarbeitet.
+
+configure-link-mode-channel = ✅ Link-Modus für { $channel } auf **{ $mode }** gesetzt.
+configure-link-mode-server = ✅ Link-Modus des Servers auf **{ $mode }** gesetzt.
+configure-link-mode-channel-reset = ✅ { $channel } nutzt wieder den Link-Modus des Servers.
+configure-link-mode-server-reset = ✅ Link-Modus des Servers auf **{ $mode }** zurückgesetzt.
+
+configure-tag-policy-needs-channel = ❌ Zum Melden wird ein `mod_channel` benötigt.
+configure-tag-policy-set = ✅ Umgang mit fremden Tags: **{ $policy }**.
+configure-tag-policy-set-with-channel = ✅ Umgang mit fremden Tags: **{ $policy }**, Meldungen gehen an { $channel }.
+
+configure-hint-reset = ✅ Hinweis zurückgesetzt. Er lautet jetzt:
+    > { $hint }
+    -# Wird nach { $seconds } Sekunden gelöscht.
+configure-hint-current = Aktueller Hinweis:
+    > { $hint }
+    -# Wird nach { $seconds } Sekunden gelöscht.
+configure-hint-saved = ✅ Hinweis gespeichert. Er lautet jetzt:
+    > { $hint }
+    -# Wird nach { $seconds } Sekunden gelöscht.
+
+configure-dedup-set = ✅ Wiederholte Produkte: **{ $mode }**.
+configure-dedup-set-with-window = ✅ Wiederholte Produkte: **{ $mode }** innerhalb von { $minutes } Minuten nach der Antwort des Bots.
+
+configure-product-api-removed = ✅ Product-API-Schlüssel entfernt.
+configure-product-api-saved = ✅ Product-API-Schlüssel gespeichert. Amazon-Produktlinks bekommen jetzt Embeds mit Titel, Bild, Preis und Bewertung.
+configure-product-api-incomplete = ❌ Bitte gib einen Access Key und einen Secret Key ein.
+configure-product-api-modal-title = 🔑 Product Advertising API
+configure-product-api-modal-access-key = Access Key
+configure-product-api-modal-secret-key = Secret Key
+configure-product-api-modal-secret-keep = Leer lassen, um den gespeicherten Secret Key zu behalten
+configure-product-api-modal-partner-tag = Partner-Tag (optional)
+configure-product-api-modal-partner-tag-placeholder = Standard: dein Tag der Region des Links
+
+configure-deals-channel-set = ✅ Preisalarme werden in { $channel } gepostet.
+configure-deals-channel-dm = ✅ Preisalarme werden per DM gesendet.
+
+configure-also-offer-unknown = ❌ `{ $marketplace }` ist kein Amazon-Marktplatz mit Partnerprogramm.
+configure-also-offer-too-many = ❌ Bitte wähle höchstens { $max } Marktplätze.
+configure-also-offer-off = ✅ Produktlinks bekommen nur einen Button für ihren eigenen Marktplatz.
+configure-also-offer-set = ✅ Amazon-Produktlinks bekommen zusätzlich Buttons für: { $marketplaces }.
+
+configure-language-set = ✅ Der Bot spricht auf diesem Server jetzt { $language }.
+configure-language-auto = ✅ Der Bot beantwortet Befehle jetzt in der Discord-Sprache des jeweiligen Mitglieds und schreibt in Kanälen in der Sprache des Servers.
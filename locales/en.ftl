@@ -0,0 +1,263 @@
+# English catalog; every message the bot sends. Other catalogs fall back to these messages.
+# Slash commands and options are described by the `cmd-*` messages; a `.name` attribute renames them.
+
+language-name = English
+
+## Slash commands
+
+cmd-amazon = Clean and tag your Amazon or eBay link
+cmd-amazon-url = Your raw Amazon or eBay URL, e.g. https://amzn.to/...
+cmd-amazon-region = Get the link for this Amazon marketplace instead (product links only)
+
+cmd-stats = Show link generation statistics
+
+cmd-watch = Get an alert when an Amazon product gets cheaper
+cmd-watch-add = Watch the price of an Amazon product
+cmd-watch-add-link = Amazon product link
+cmd-watch-add-target-price = Alert below this price (default: below the current price)
+cmd-watch-list = Show your price watches
+cmd-watch-remove = Stop watching a product
+cmd-watch-remove-id = Watch ID from /watch list
+
+cmd-configure = 🌍 Configure affiliate tracking for Amazon marketplaces and other networks
+cmd-configure-region = Set tracking tag, footer and kept URL parameters for a region
+cmd-configure-region-region = Amazon region to configure
+cmd-configure-network = Set the tracking ID for another affiliate network (eBay, ...)
+cmd-configure-network-network = Affiliate network to configure
+cmd-configure-network-tracking-id = Your tracking ID, e.g. the EPN campaign ID; leave empty to remove it
+cmd-configure-rule = Rewrite rules for shops without built-in support
+cmd-configure-rule-add = Add or replace the rule for a shop domain
+cmd-configure-rule-add-host = Shop domain, subdomains included, e.g. aliexpress.com
+cmd-configure-rule-add-strip = Query parameters to remove, e.g. utm_source, aff_id (* removes all)
+cmd-configure-rule-add-add = Query parameters to add, e.g. ref=ourserver
+cmd-configure-rule-list = Show this server's rewrite rules
+cmd-configure-rule-remove = Remove a rewrite rule
+cmd-configure-rule-remove-id = Rule ID from /configure rule list
+cmd-configure-rule-test = Show how a sample URL would be rewritten
+cmd-configure-rule-test-url = Sample URL, e.g. https://shop.example/item/1?utm_source=x
+cmd-configure-channels = Choose the channels and categories where links are processed
+cmd-configure-channels-allow = Process links only in allowed channels and categories
+cmd-configure-channels-deny = Never process links in this channel or category
+cmd-configure-channels-remove = Remove a channel or category from the lists
+cmd-configure-channels-list = Show the allowed and denied channels
+cmd-configure-channels-channel = Channel or category; threads follow their parent channel
+cmd-configure-roles = Exempt roles from link processing, or only process some roles
+cmd-configure-roles-exempt = Never process links posted by members with this role
+cmd-configure-roles-only = Only process links posted by members with one of these roles
+cmd-configure-roles-remove = Remove a role from the lists
+cmd-configure-roles-list = Show exempt and processed roles
+cmd-configure-roles-role = Role
+cmd-configure-link-mode = Choose how the bot handles posted links, server-wide or per channel
+cmd-configure-link-mode-mode = How the bot responds to posted links
+cmd-configure-link-mode-channel = Only change this channel (default: whole server)
+cmd-configure-tag-policy = Handle links that already carry an affiliate tag
+cmd-configure-tag-policy-policy = What to do with links that already carry someone else's affiliate tag
+cmd-configure-tag-policy-mod-channel = Channel for flagged messages (required for the flag policy)
+cmd-configure-hint = Customize the hint shown when a link-only message is deleted
+cmd-configure-hint-text = Hint text; placeholders: {"{{"}sender{"}}"}, {"{{"}command{"}}"}, {"{{"}lifetime{"}}"}
+cmd-configure-hint-lifetime = Seconds until the hint is deleted again (default: 10)
+cmd-configure-hint-reset = Go back to the built-in hint and lifetime
+cmd-configure-dedup = Handle the same product posted repeatedly in a channel
+cmd-configure-dedup-mode = What to do when a product is posted again shortly after the bot answered it
+cmd-configure-dedup-window = Minutes a product counts as answered (default: 10)
+cmd-configure-product-api = Enter your own Product Advertising API keys for product embeds in a form
+cmd-configure-product-api-remove = Remove your keys and use the bot's, if it has any
+cmd-configure-deals-channel = Post price watch alerts in a channel instead of DMs
+cmd-configure-deals-channel-channel = Channel for alerts (leave out to send alerts by DM)
+cmd-configure-also-offer = Add buttons for the same product on other Amazon marketplaces
+cmd-configure-also-offer-marketplaces = Up to 4 marketplaces, e.g. "com, co.uk" ("none" to turn off)
+cmd-configure-language = Choose the language of the bot's messages in this server
+cmd-configure-language-language = Language (automatic: each member's Discord language)
+
+## Choices
+
+link-mode-delete-and-hint = Delete link-only messages with a hint (default)
+link-mode-button-reply = Reply with link buttons
+link-mode-text-reply = Reply with the cleaned links
+link-mode-suppress-embeds-and-reply = Hide original previews and reply with the links
+link-mode-webhook-repost = Repost the message with cleaned links (webhook)
+link-mode-off = Off, ignore links
+link-mode-reset = Reset (channel: use server mode, server: default)
+
+tag-policy-replace = Replace with our tag (default)
+tag-policy-keep = Keep their tag, leave the link alone
+tag-policy-reject = Reject the message
+tag-policy-flag = Replace and flag to mod channel
+
+dedup-mode-off = Answer every post (default)
+dedup-mode-react = React with 🔁
+dedup-mode-jump-link = Reply with a link to the earlier answer
+dedup-mode-silent = Stay quiet
+
+language-auto = Automatic (each member's Discord language)
+
+## Link replies in chat
+
+button-amazon-product = 🛒 View on Amazon
+button-amazon-search = 🔍 Search on Amazon
+button-amazon-store = 🏬 Visit Store
+button-amazon-wishlist = 🎁 View Wishlist
+button-amazon-idea-list = 💡 View Idea List
+button-amazon-deals = 🔥 View Deals
+button-ebay-item = 🛒 View on eBay
+button-ebay-search = 🔍 Search on eBay
+button-open-link = 🔗 Open Link
+
+footer-default = Using this link you support our server!
+footer-recommended = { $sender } recommended this. { $footer }
+
+hint-default = { $sender }, please use { $command } to clean and tag your URL.
+cooldown = ⏳ Slow down a little, links are cleaned again in { $seconds ->
+    [one] { $seconds } second
+   *[other] { $seconds } seconds
+}.
+foreign-tag-rejected = { $sender }, links with third-party affiliate tags are not allowed here.
+foreign-tag-report = 🚩 { $sender } posted a link with an existing affiliate tag in { $channel }: { $link }
+dedup-already-shared = 🔁 Already shared here: { $link }
+
+product-ratings = { $count } ratings
+product-disclaimer = Price and availability as of the time shown, subject to change
+
+## /amazon
+
+link-blocked = This link can't be resolved. Only Amazon and eBay links are supported.
+link-timed-out = The shop took too long to respond. Please try again in a moment.
+link-resolve-failed = Could not resolve this short link. Please try again or paste the full URL.
+link-no-tag = No tracking tag available for this region.
+link-unsupported = Could not parse this link. Ensure it's a valid Amazon or eBay URL.
+link-not-convertible = Only Amazon product links can be converted to another marketplace.
+
+## /stats
+
+stats-error = ❌ Unable to fetch statistics. Please try again later.
+stats-title = 📊 Affilify Statistics
+stats-description = Link generation statistics for this server
+stats-global = 🌐 Global Total
+stats-server = 🏠 This Server
+stats-top-regions = 📈 Top Regions
+stats-no-regions = No regions yet
+stats-links = { $count ->
+    [one] { $count } link
+   *[other] { $count } links
+}
+stats-clicks = { $count ->
+    [one] { $count } click in { $days } days
+   *[other] { $count } clicks in { $days } days
+}
+stats-custom-rule = 🔧 **{ $host }** (custom rule)
+stats-footer = Keep sharing those affiliate links! 💰
+
+## /watch
+
+watch-removed = ✅ Watch removed.
+watch-not-found = ❌ No watch with this ID. See `/watch list`.
+watch-remove-error = ❌ Error removing watch: { $error }
+watch-limit = ❌ You can watch at most { $max } products. Remove one with `/watch remove`.
+watch-resolve-failed = ❌ Could not resolve this short link. Please paste the full product URL.
+watch-not-a-product = ❌ Please use a link to an Amazon product page.
+watch-price-unknown = ❌ The current price of this product is unknown, please set a `target_price`.
+watch-added = 👀 Watching `{ $asin }` on { $domain } (ID { $id }). You'll get an alert once it's below **{ $target }**.
+watch-added-with-price = 👀 Watching `{ $asin }` on { $domain } (ID { $id }). Current price: **{ $price }**. You'll get an alert once it's below **{ $target }**.
+watch-save-error = ❌ Error saving watch: { $error }
+watch-list-empty = You're not watching any products. Start with `/watch add`.
+watch-list-title = **Your price watches:**
+watch-list-entry = `{ $id }` · <{ $url }> · below **{ $target }**
+watch-alert = 📉 **Price drop:** now **{ $price }** (your target: below { $target })
+    { $link }
+    -# This watch has ended. Use `/watch add` to watch the product again.
+
+## /configure
+
+configure-server-only = This command can only be used in a server.
+configure-not-admin = You must be a server administrator or the server owner to run this command.
+configure-save-error = ❌ Error saving configuration: { $error }
+
+configure-unknown-region = ❌ Unknown or unsupported Amazon region `{ $region }`. Please pick one from the list.
+configure-global-region = 🌍 Global Settings (All Regions)
+configure-modal-title = { $flag } Configure { $domain }
+configure-modal-title-global = 🌍 Global Amazon Configuration
+configure-modal-tag = 🏷️ Tracking Tag for { $region }
+configure-modal-footer = 💬 Custom Footer (optional)
+configure-modal-footer-placeholder = {"{{"}sender{"}}"} recommended this and supports our server!
+configure-modal-params = 🔗 Kept URL Parameters (server-wide)
+configure-updated-global = ✅ Global configuration updated!
+    🌍 { $count } regions configured
+configure-updated = ✅ Configuration updated for { $region }!
+    🌍 { $count } items configured
+configure-no-changes = ℹ️ No changes made.
+
+configure-tracking-id-invalid = ❌ Tracking IDs may only contain letters, digits, `-` and `_`.
+configure-tracking-id-set = ✅ { $network } tracking ID set to `{ $id }`.
+configure-tracking-id-removed = ✅ { $network } tracking ID removed, links use the default.
+
+configure-rule-saved = ✅ Rule `#{ $id }` saved for `{ $host }`
+    • Strip: `{ $strip }`
+    • Add: `{ $add }`
+configure-rule-save-error = ❌ Error saving rule: { $error }
+configure-rule-invalid = ❌ Invalid rule: { $error }.
+configure-rule-list-empty = ℹ️ No rewrite rules yet. Add one with `/configure rule add`.
+configure-rule-entry = `#{ $id }` **{ $host }** — strip `{ $strip }`, add `{ $add }`
+configure-rule-removed = ✅ Rule removed.
+configure-rule-not-found = ❌ No rule with this ID. See `/configure rule list`.
+configure-rule-remove-error = ❌ Error removing rule: { $error }
+configure-rule-builtin = ℹ️ This link is handled by the built-in Amazon/eBay logic, rules don't apply to it.
+configure-rule-matches = 🔧 Rule `#{ $id }` ({ $host }) matches:
+    { $url }
+configure-rule-invalid-url = ❌ This is not a valid URL.
+configure-rule-no-match = ℹ️ No rule matches this URL, it would be left alone.
+
+configure-channels-list = ✅ **Allowed:** { $allowed }
+    ⛔ **Denied:** { $denied }
+    -# With an allowlist, links are only processed in allowed channels. The most specific entry wins, threads follow their parent.
+configure-not-listed = ℹ️ { $target } is not on a list.
+configure-unlisted = ✅ { $target } removed from the lists.
+configure-channel-allowed = ✅ Links are processed in { $channel }.
+configure-channel-denied = ✅ Links are no longer processed in { $channel }.
+configure-roles-list = 🛡️ **Exempt:** { $exempt }
+    ✅ **Only these roles:** { $only }
+    -# Exempt roles always win. Without "only" roles, everyone else is processed.
+configure-role-exempt = ✅ Links from members with { $role } are left alone.
+configure-role-only = ✅ Links from members with { $role } are processed.
+
+configure-link-mode-channel = ✅ Link mode for { $channel } set to **{ $mode }**.
+configure-link-mode-server = ✅ Server link mode set to **{ $mode }**.
+configure-link-mode-channel-reset = ✅ { $channel } uses the server link mode again.
+configure-link-mode-server-reset = ✅ Server link mode reset to **{ $mode }**.
+
+configure-tag-policy-needs-channel = ❌ The flag policy needs a `mod_channel`.
+configure-tag-policy-set = ✅ Affiliate tag policy set to **{ $policy }**.
+configure-tag-policy-set-with-channel = ✅ Affiliate tag policy set to **{ $policy }**, reports go to { $channel }.
+
+configure-hint-reset = ✅ Hint reset. It now reads:
+    > { $hint }
+    -# Deleted after { $seconds } seconds.
+configure-hint-current = Current hint:
+    > { $hint }
+    -# Deleted after { $seconds } seconds.
+configure-hint-saved = ✅ Hint saved. It now reads:
+    > { $hint }
+    -# Deleted after { $seconds } seconds.
+
+configure-dedup-set = ✅ Repeated products: **{ $mode }**.
+configure-dedup-set-with-window = ✅ Repeated products: **{ $mode }** within { $minutes } minutes of the bot's answer.
+
+configure-product-api-removed = ✅ Product API keys removed.
+configure-product-api-saved = ✅ Product API keys saved. Amazon product links now get embeds with title, image, price and rating.
+configure-product-api-incomplete = ❌ Please enter an access key and a secret key.
+configure-product-api-modal-title = 🔑 Product Advertising API
+configure-product-api-modal-access-key = Access key
+configure-product-api-modal-secret-key = Secret key
+configure-product-api-modal-secret-keep = Leave empty to keep the saved secret key
+configure-product-api-modal-partner-tag = Partner tag (optional)
+configure-product-api-modal-partner-tag-placeholder = Default: your tag for the link's region
+
+configure-deals-channel-set = ✅ Price alerts are posted in { $channel }.
+configure-deals-channel-dm = ✅ Price alerts are sent by DM.
+
+configure-also-offer-unknown = ❌ `{ $marketplace }` is not an Amazon marketplace with an Associates program.
+configure-also-offer-too-many = ❌ Please choose at most { $max } marketplaces.
+configure-also-offer-off = ✅ Product links only get a button for their own marketplace.
+configure-also-offer-set = ✅ Amazon product links also get buttons for: { $marketplaces }.
+
+configure-language-set = ✅ The bot now speaks { $language } in this server.
+configure-language-auto = ✅ The bot now answers commands in each member's Discord language, and in the server's language in channels.
@@ -0,0 +1,3 @@
+# Aviso español para mensajes que solo contienen un enlace; todo lo demás viene de en.ftl.
+
+hint-default = { $sender }, usa { $command } para limpiar y etiquetar tu enlace.
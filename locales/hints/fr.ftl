@@ -0,0 +1,3 @@
+# Indice français pour les messages composés d'un seul lien ; tout le reste vient de en.ftl.
+
+hint-default = { $sender }, merci d'utiliser { $command } pour nettoyer et taguer ton lien.
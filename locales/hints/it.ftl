@@ -0,0 +1,3 @@
+# Avviso italiano per i messaggi con solo un link; tutto il resto viene da en.ftl.
+
+hint-default = { $sender }, usa { $command } per pulire e taggare il tuo link.
@@ -0,0 +1,78 @@
+// src/cleanup.rs
+// Deletes the bot's temporary messages (hints, notices) after their lifetime.
+// Pending deletions are stored in SQLite and finished on startup, so a restart never leaves them behind.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use rusqlite::params;
+use serenity::all::{ChannelId, MessageId};
+use serenity::http::Http;
+use super::db;
+
+/// Set once the stored deletions were picked up; `ready` fires again after reconnects
+static RESUMED: AtomicBool = AtomicBool::new(false);
+/// Deletions that still failed this long after their time are given up; the message is most likely gone
+pub const GIVE_UP_SECS: i64 = 24 * 60 * 60;
+
+/// Delete a message after `lifetime`, even if the bot restarts in between
+pub fn delete_later(http: &Arc<Http>, channel_id: ChannelId, message_id: MessageId, lifetime: Duration) {
+    let delete_at = chrono::Utc::now().timestamp() + lifetime.as_secs() as i64;
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO pending_deletions (message_id, channel_id, delete_at) VALUES (?, ?, ?)",
+            params![message_id.get().to_string(), channel_id.get().to_string(), delete_at],
+        )
+    });
+    tokio::spawn(delete_at_time(http.clone(), channel_id, message_id, delete_at));
+}
+
+/// Schedule the deletions stored by earlier runs; overdue messages are deleted right away
+pub fn resume(http: &Arc<Http>) {
+    if RESUMED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let pending = db::with_connection(|conn| {
+        let mut stmt = conn.prepare("SELECT message_id, channel_id, delete_at FROM pending_deletions")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap_or_default();
+
+    for (message_id, channel_id, delete_at) in pending {
+        let ids = message_id.parse::<u64>().ok().zip(channel_id.parse::<u64>().ok())
+            .filter(|(message_id, channel_id)| *message_id != 0 && *channel_id != 0);
+        match ids {
+            Some((message_id, channel_id)) => {
+                tokio::spawn(delete_at_time(http.clone(), ChannelId::new(channel_id), MessageId::new(message_id), delete_at));
+            },
+            None => forget(&message_id),
+        }
+    }
+}
+
+async fn delete_at_time(http: Arc<Http>, channel_id: ChannelId, message_id: MessageId, delete_at: i64) {
+    let wait = delete_at - chrono::Utc::now().timestamp();
+    if wait > 0 {
+        tokio::time::sleep(Duration::from_secs(wait as u64)).await;
+    }
+    let done = match channel_id.delete_message(&http, message_id).await {
+        Ok(_) => true,
+        // Already deleted, channel gone or no access: retrying won't help.
+        // Network errors and Discord outages are retried on the next start.
+        Err(serenity::Error::Http(e)) => e.status_code().is_some_and(|status| status.is_client_error()),
+        Err(_) => false,
+    };
+    if done {
+        forget(&message_id.get().to_string());
+    }
+}
+
+fn forget(message_id: &str) {
+    let _ = db::with_connection(|conn| {
+        conn.execute("DELETE FROM pending_deletions WHERE message_id = ?", params![message_id])
+    });
+}
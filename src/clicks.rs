@@ -0,0 +1,181 @@
+// src/clicks.rs
+// Click tracking: with a public base URL configured, link buttons and `/amazon` answers point to
+// short links like `https://go.example/r/{id}` on the bot's own HTTP server, which logs the click
+// and redirects to the tagged URL.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use hyper::header::{HeaderValue, LOCATION, USER_AGENT};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use ring::rand::{SecureRandom, SystemRandom};
+use rusqlite::{params, OptionalExtension};
+use serenity::all::MessageId;
+use super::{config, db, utils::{self, ProcessedLink}};
+
+/// Random bytes per short link id (12 hex characters)
+const ID_BYTES: usize = 6;
+/// How long clicks are kept; `/stats` counts the clicks of this period
+pub const CLICK_RETENTION_DAYS: i64 = 90;
+/// How long short links redirect after they were created; older ones are deleted and answer 404
+pub const LINK_RETENTION_DAYS: i64 = 365;
+
+/// Whether links are posted as short links through the redirect server
+pub fn enabled() -> bool {
+    config::click_base_url().is_some()
+}
+
+/// URL to post for a link: a short link through the redirect server if click tracking is on,
+/// otherwise the cleaned link itself. `message_id` is the member's message the link was posted in.
+/// The same link in the same message keeps its short link, e.g. when the message is edited.
+pub fn url_for(link: &ProcessedLink, guild_id: &str, message_id: Option<MessageId>) -> String {
+    let Some(base_url) = config::click_base_url() else {
+        return link.clean_url.clone();
+    };
+    let Some(new_id) = new_id() else {
+        return link.clean_url.clone();
+    };
+    let message_id = message_id.map(|id| id.get().to_string());
+    let id = db::with_connection(|conn| {
+        let existing = conn.query_row(
+            "SELECT id FROM short_links WHERE guild_id = ? AND message_id IS ? AND target_url = ?",
+            params![guild_id, message_id, link.clean_url],
+            |r| r.get::<_, String>(0),
+        ).optional()?;
+        if let Some(id) = existing {
+            return Ok(id);
+        }
+        conn.execute(
+            "INSERT INTO short_links (id, target_url, guild_id, message_id, provider, region, item_id, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                new_id,
+                link.clean_url,
+                guild_id,
+                message_id,
+                link.network,
+                link.region,
+                link.item_id,
+                chrono::Utc::now().timestamp()
+            ],
+        )?;
+        Ok(new_id)
+    });
+    match id {
+        Ok(id) => format!("{}/r/{}", base_url, id),
+        // Better an untracked link than none
+        Err(_) => link.clean_url.clone(),
+    }
+}
+
+fn new_id() -> Option<String> {
+    let mut bytes = [0u8; ID_BYTES];
+    SystemRandom::new().fill(&mut bytes).ok()?;
+    Some(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Start the redirect server if a public base URL is configured
+pub fn start() {
+    if !enabled() {
+        return;
+    }
+    let addr: SocketAddr = match config::click_listen_addr().parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            eprintln!("Invalid CLICK_LISTEN_ADDR: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(handle)) });
+        match Server::try_bind(&addr) {
+            Ok(server) => {
+                println!("🔗 Click tracking listening on {}", addr);
+                if let Err(e) = server.serve(make_service).await {
+                    eprintln!("Click tracking server error: {}", e);
+                }
+            },
+            Err(e) => eprintln!("Failed to start click tracking server on {}: {}", addr, e),
+        }
+    });
+}
+
+/// A short link as stored for its redirect
+struct ShortLink {
+    id: String,
+    target_url: String,
+    guild_id: String,
+    message_id: Option<String>,
+    region: String,
+    item_id: Option<String>,
+}
+
+fn find(id: &str) -> Option<ShortLink> {
+    db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT id, target_url, guild_id, message_id, region, item_id FROM short_links WHERE id = ?",
+            params![id],
+            |r| Ok(ShortLink {
+                id: r.get(0)?,
+                target_url: r.get(1)?,
+                guild_id: r.get(2)?,
+                message_id: r.get(3)?,
+                region: r.get(4)?,
+                item_id: r.get(5)?,
+            }),
+        ).optional()
+    })
+    .ok()
+    .flatten()
+}
+
+/// Log a click; only a hash of the user agent is stored
+fn log_click(link: &ShortLink, user_agent: &str) {
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO clicks (short_link_id, guild_id, message_id, region, item_id, user_agent_hash, clicked_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                link.id,
+                link.guild_id,
+                link.message_id,
+                link.region,
+                link.item_id,
+                utils::sha256_hex(user_agent.as_bytes()),
+                chrono::Utc::now().timestamp()
+            ],
+        )
+    });
+}
+
+/// `GET /r/{id}`: log the click and redirect to the tagged URL
+async fn handle(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let link = match (req.method(), req.uri().path().strip_prefix("/r/")) {
+        (&Method::GET | &Method::HEAD, Some(id)) if !id.is_empty() => find(id),
+        _ => None,
+    };
+    let Some(link) = link else {
+        return Ok(status(StatusCode::NOT_FOUND));
+    };
+    let Ok(location) = HeaderValue::from_str(&link.target_url) else {
+        return Ok(status(StatusCode::INTERNAL_SERVER_ERROR));
+    };
+
+    // Link previews (Discordbot, ...) and HEAD requests are no clicks
+    let user_agent = req.headers().get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if req.method() == Method::GET && !user_agent.to_lowercase().contains("bot") {
+        log_click(&link, user_agent);
+    }
+
+    let mut response = status(StatusCode::FOUND);
+    response.headers_mut().insert(LOCATION, location);
+    Ok(response)
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = code;
+    response
+}
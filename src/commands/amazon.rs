@@ -1,29 +1,30 @@
 // src/commands/amazon.rs
 // Handles the `/amazon` slash command: cleans URLs, tags them, and logs usage.
+// Despite its name it accepts links of every supported network (Amazon, eBay).
 
 use serenity::all::{
     Command, CommandInteraction, CommandOptionType,
-    CreateCommand, CreateCommandOption,
-    CreateInteractionResponse, CreateInteractionResponseMessage,
-    InstallationContext, InteractionContext,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+    EditInteractionResponse, InstallationContext, InteractionContext,
 };
 use serenity::http::Http;
 use serenity::prelude::*;
-use rusqlite::params;
-use super::super::{db, utils, config};
+use super::super::{clicks, i18n, marketplace, paapi, ratelimit, resolver, rules, utils};
 
-/// Register the `/amazon` slash command with a URL option.
+/// Register the `/amazon` slash command with a URL option and an optional target marketplace.
 pub async fn register_commands(http: &Http) {
-    let command = CreateCommand::new("amazon")
-        .description("Clean and tag your Amazon link")
+    // Marketplaces a product link can be converted to; Discord allows up to 25 choices
+    let mut region = i18n::option(CommandOptionType::String, "region", "cmd-amazon-region");
+    for marketplace in marketplace::MARKETPLACES.iter().filter(|m| m.associates).take(25) {
+        region = region.add_string_choice(marketplace.label(), marketplace.tld);
+    }
+
+    let command = i18n::command("amazon", "cmd-amazon")
         .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                "url",
-                "Your raw Amazon URL, e.g. https://amzn.to/..."
-            )
-            .required(true)
+            i18n::option(CommandOptionType::String, "url", "cmd-amazon-url")
+                .required(true)
         )
+        .add_option(region)
         .dm_permission(true)
         // *** WICHTIG für DM-Verwendung: ***
         // 1) App darf als User-App installiert werden (für DMs):
@@ -43,109 +44,140 @@ pub async fn register_commands(http: &Http) {
 
 /// Handler for the `/amazon` command.
 /// - Resolves short URLs
-/// - Parses ASIN and region
+/// - Finds the network and classifies the link (product, search, store, wishlist, ...) and region
 /// - Retrieves tracking tag and footer template
+/// - Converts product links to the requested marketplace, if any
 /// - Logs usage in the database
+/// - Posts a click-tracking short link instead if a public base URL is configured
 /// - Replies with a plain message: cleaned link + footer
+///
+/// Resolving short links and looking up products can take longer than Discord's 3 seconds, so the answer is deferred.
 pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     // Check if this is a DM or Guild interaction
     let is_dm = cmd.guild_id.is_none();
     let guild_id = cmd.guild_id.map(|id| id.get().to_string()).unwrap_or_else(|| "DM".to_string());
+    let guild_ref = if is_dm { None } else { Some(guild_id.as_str()) };
+    let locale = i18n::resolve(guild_ref, &cmd.locale);
+
+    // Rate limits are shared with links posted in chat
+    if let Err(limited) = ratelimit::acquire(cmd.user.id, cmd.channel_id, cmd.guild_id, 1) {
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(ratelimit::cooldown_message(&limited, locale))
+                .ephemeral(true)
+        );
+        let _ = cmd.create_response(&ctx.http, response).await;
+        return;
+    }
 
-    // Extract raw URL argument
-    let url_raw = cmd.data.options.first()
+    if cmd.defer(&ctx.http).await.is_err() {
+        return;
+    }
+
+    // Extract raw URL argument and the optional target marketplace
+    let url_raw = cmd.data.options.iter()
+        .find(|opt| opt.name == "url")
         .and_then(|opt| opt.value.as_str())
         .unwrap_or("")
         .to_string();
+    let target_region = cmd.data.options.iter()
+        .find(|opt| opt.name == "region")
+        .and_then(|opt| opt.value.as_str());
 
-    // Resolve redirects
-    let resolved = utils::resolve_url(&url_raw).await.unwrap_or_else(|_| url_raw.clone());
-
-    // Parse ASIN and region
-    if let Some((asin, region)) = utils::parse_amazon_url(&resolved) {
-        // Determine tracking tag and footer based on context (DM vs Guild)
-        let (tag, footer_template) = if is_dm {
-            // Use default developer tags and signature for DMs
-            let default_tag = config::default_tracking_tag(&region);
-            let default_signature = config::default_signature();
-            (default_tag, default_signature)
-        } else {
-            // Try to get guild-specific settings, fallback to defaults
-            let default_template = "Using this link you support our server!".to_string();
-            
-            let (guild_tag, guild_footer) = db::with_connection(|conn| {
-                let tag: String = conn.query_row(
-                    "SELECT tracking_tag FROM guild_affiliates WHERE guild_id = ? AND region = ?",
-                    params![guild_id, region],
-                    |r| r.get(0),
-                ).unwrap_or_else(|_| String::new());
-                
-                let footer: String = conn.query_row(
-                        "SELECT footer_text FROM guild_settings WHERE guild_id = ?",
-                        params![guild_id],
-                        |r| r.get(0),
-                    )
-                    .unwrap_or_else(|_| default_template.clone());
-                Ok((tag, footer))
-            })
-            .unwrap_or((String::new(), default_template.clone()));
-            
-            // If no guild tag configured, use default developer tag
-            if guild_tag.is_empty() {
-                let default_tag = config::default_tracking_tag(&region);
-                let default_signature = config::default_signature();
-                (default_tag, default_signature)
-            } else {
-                (guild_tag, guild_footer)
+    // Accept links pasted without a protocol, e.g. "amzn.to/abc"
+    let url_raw = if url_raw.contains("://") { url_raw } else { format!("https://{}", url_raw.trim()) };
+
+    // Resolve redirects for short links; only hosts of supported networks are ever requested
+    let resolved = if utils::is_short_link(&url_raw) {
+        match resolver::resolve_url(&url_raw).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                let content = if e.is_blocked() {
+                    "link-blocked"
+                } else if matches!(e, resolver::ResolveError::TimedOut) {
+                    "link-timed-out"
+                } else {
+                    "link-resolve-failed"
+                };
+                // Only the user sees why the link failed: the deferred public answer is replaced by a private one
+                let _ = cmd.delete_response(&ctx.http).await;
+                let followup = CreateInteractionResponseFollowup::new()
+                    .content(i18n::text(locale, content))
+                    .ephemeral(true);
+                let _ = cmd.create_followup(&ctx.http, followup).await;
+                return;
             }
-        };
-
-        // If still no tag available, inform user
-        if tag.is_empty() {
-            let response = CreateInteractionResponse::Message(
-                CreateInteractionResponseMessage::new()
-                    .content("No tracking tag available for this region.")
-            );
-            let _ = cmd.create_response(&ctx.http, response).await;
-            return;
         }
+    } else {
+        url_raw.clone()
+    };
 
-        // Log usage
-        let _ = db::with_connection(|conn| {
-            conn.execute(
-                "INSERT INTO link_stats (guild_id, region) VALUES (?, ?)",
-                params![guild_id, region],
-            )
-        });
+    // Find the network and page kind, then clean and tag the link
+    // Links of other shops are rewritten by the server's rules, if one matches
+    let rewritten = guild_ref.and_then(|guild| {
+        let rules = rules::for_guild(guild);
+        let rule = rules::matching(&rules, &resolved).filter(|_| !utils::is_affiliate_url(&resolved))?;
+        utils::rewrite_link(&resolved, rule, guild)
+    });
+    let result = match rewritten {
+        Some(link) => Ok(link),
+        None => utils::clean_link(&resolved, guild_ref),
+    };
+    // The same product on the requested marketplace; only Amazon product pages can be converted
+    let converted = match (result, target_region) {
+        (Ok(link), Some(tld)) if link.region != tld => match utils::convert_link(&link, tld, guild_ref) {
+            Err(utils::LinkError::Unsupported) => Err("link-not-convertible"),
+            converted => converted.map_err(|_| "link-no-tag"),
+        },
+        (result, _) => result.map_err(|e| match e {
+            // If still no tag available, inform user
+            utils::LinkError::NoTag => "link-no-tag",
+            // Parsing failed
+            utils::LinkError::Unsupported => "link-unsupported",
+        }),
+    };
+    let link = match converted {
+        Ok(link) => link,
+        Err(content) => {
+            let response = EditInteractionResponse::new().content(i18n::text(locale, content));
+            let _ = cmd.edit_response(&ctx.http, response).await;
+            return;
+        }
+    };
 
-        // Build cleaned URL
-        let clean_url = format!("https://amazon.{}/dp/{}/?tag={}", region, asin, tag);
+    // Log usage
+    utils::log_link(&guild_id, link.network, &link.region);
 
-        // Construct footer with sender mention support (only in guilds, not DMs)
-        let footer = if is_dm {
-            footer_template
+    // Construct footer with sender mention support (only in guilds, not DMs)
+    let footer_template = &link.footer_template;
+    let footer = if is_dm {
+        footer_template.clone()
+    } else {
+        let sender_mention = format!("<@{}>", cmd.user.id.get());
+        if footer_template.contains("{{sender}}") {
+            footer_template.replace("{{sender}}", &sender_mention)
         } else {
-            let sender_mention = format!("<@{}>", cmd.user.id.get());
-            if footer_template.contains("{{sender}}") {
-                footer_template.replace("{{sender}}", &sender_mention)
-            } else {
-                format!("{} recommended this. {}", sender_mention, footer_template)
-            }
-        };
+            i18n::format(locale, "footer-recommended", &[
+                ("sender", sender_mention.into()),
+                ("footer", footer_template.as_str().into()),
+            ])
+        }
+    };
 
-        // Send plain message: link + "-# footer"
-        let response_content = format!("{}\n-# {}", clean_url, footer);
-        let response = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new()
-                .content(response_content)
-        );
-        let _ = cmd.create_response(&ctx.http, response).await;
-    } else {
-        // Parsing failed
-        let response = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new()
-                .content("Could not parse Amazon URL. Ensure it's valid.")
-        );
-        let _ = cmd.create_response(&ctx.http, response).await;
-    }
+    // Product details for Amazon products if the Product Advertising API is set up
+    let product = paapi::product_for(&link, guild_ref).await;
+
+    // A short link through the redirect server if click tracking is on
+    let url = clicks::url_for(&link, &guild_id, None);
+
+    // Send plain message: link + "-# footer", with a product embed instead of Discord's preview if available
+    let mut message = EditInteractionResponse::new();
+    let response_content = match &product {
+        Some(product) => {
+            message = message.embed(paapi::embed(product, &url, locale));
+            format!("<{}>\n-# {}", url, footer)
+        },
+        None => format!("{}\n-# {}", url, footer),
+    };
+    let _ = cmd.edit_response(&ctx.http, message.content(response_content)).await;
 }
@@ -1,32 +1,197 @@
 // src/commands/configure.rs
-// Handles the `/configure` slash command: sets tracking tags and footer text per region.
+// Handles the `/configure` slash command: sets tracking tags and footer text per region,
+// tracking IDs for other affiliate networks, rewrite rules for other shops, where, whose and how posted links are handled,
+// and the guild's policy for links with existing affiliate tags.
 
 use serenity::all::{
-    Command, CommandInteraction, CommandOptionType, 
-    CreateCommand, CreateCommandOption,
+    Command, CommandInteraction, CommandOptionType, CreateCommandOption,
     CreateInteractionResponse, CreateInteractionResponseMessage,
     CreateModal, CreateInputText, InputTextStyle, CreateActionRow,
     CreateAutocompleteResponse, Interaction,
-    ActionRowComponent, Permissions,
+    ActionRowComponent, Permissions, ChannelType, Mentionable,
+    ResolvedOption, ResolvedValue,
     InstallationContext, InteractionContext,
 };
 use serenity::http::Http;
 use serenity::prelude::*;
 use rusqlite::params;
-use super::super::db;
+use super::super::{db, dedup, i18n, marketplace, paapi, providers, rules, utils};
 
-/// Register the `/configure` command with autocomplete for regions.
+/// Custom ID of the `/configure product-api` modal
+pub const PRODUCT_API_MODAL: &str = "product_api_modal";
+
+/// Register the `/configure` command and its subcommands.
 pub async fn register_commands(http: &Http) {
-    let command = CreateCommand::new("configure")
-        .description("🌍 Configure affiliate tracking for Amazon marketplaces")
+    let mut policy_option = i18n::option(CommandOptionType::String, "policy", "cmd-configure-tag-policy-policy")
+        .required(true);
+    for policy in utils::ForeignTagPolicy::ALL {
+        policy_option = i18n::add_choice(policy_option, policy_label(policy), policy.as_str());
+    }
+
+    let mut dedup_option = i18n::option(CommandOptionType::String, "mode", "cmd-configure-dedup-mode")
+        .required(true);
+    for mode in dedup::DedupMode::ALL {
+        dedup_option = i18n::add_choice(dedup_option, dedup_mode_label(mode), mode.as_str());
+    }
+
+    let mut mode_option = i18n::option(CommandOptionType::String, "mode", "cmd-configure-link-mode-mode")
+        .required(true);
+    for mode in utils::LinkMode::ALL {
+        mode_option = i18n::add_choice(mode_option, link_mode_label(mode), mode.as_str());
+    }
+    mode_option = i18n::add_choice(mode_option, "link-mode-reset", "reset");
+
+    // Amazon is configured per region through `/configure region`
+    let mut network_option = i18n::option(CommandOptionType::String, "network", "cmd-configure-network-network")
+        .required(true);
+    for provider in providers::PROVIDERS.iter().filter(|p| p.id() != "amazon") {
+        network_option = network_option.add_string_choice(provider.name(), provider.id());
+    }
+
+    // Languages with a complete catalog, each labeled in its own language
+    let mut language_option = i18n::option(CommandOptionType::String, "language", "cmd-configure-language-language")
+        .required(true);
+    language_option = i18n::add_choice(language_option, "language-auto", "auto");
+    for (language, name) in i18n::languages() {
+        language_option = language_option.add_string_choice(name, language);
+    }
+
+    let command = i18n::command("configure", "cmd-configure")
         .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                "region",
-                "Amazon region to configure"
-            )
-            .required(true)
-            .set_autocomplete(true)
+            i18n::option(CommandOptionType::SubCommand, "region", "cmd-configure-region")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::String, "region", "cmd-configure-region-region")
+                        .required(true)
+                        .set_autocomplete(true)
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "network", "cmd-configure-network")
+                .add_sub_option(network_option)
+                .add_sub_option(
+                    i18n::option(CommandOptionType::String, "tracking_id", "cmd-configure-network-tracking-id")
+                        .max_length(50)
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommandGroup, "rule", "cmd-configure-rule")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "add", "cmd-configure-rule-add")
+                        .add_sub_option(
+                            i18n::option(CommandOptionType::String, "host", "cmd-configure-rule-add-host")
+                                .required(true)
+                                .max_length(253)
+                        )
+                        .add_sub_option(
+                            i18n::option(CommandOptionType::String, "strip", "cmd-configure-rule-add-strip")
+                                .max_length(500)
+                        )
+                        .add_sub_option(
+                            i18n::option(CommandOptionType::String, "add", "cmd-configure-rule-add-add")
+                                .max_length(500)
+                        )
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "list", "cmd-configure-rule-list")
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "remove", "cmd-configure-rule-remove")
+                        .add_sub_option(
+                            i18n::option(CommandOptionType::Integer, "id", "cmd-configure-rule-remove-id")
+                                .required(true)
+                        )
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "test", "cmd-configure-rule-test")
+                        .add_sub_option(
+                            i18n::option(CommandOptionType::String, "url", "cmd-configure-rule-test-url")
+                                .required(true)
+                        )
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommandGroup, "channels", "cmd-configure-channels")
+                .add_sub_option(channel_filter_option("allow", "cmd-configure-channels-allow"))
+                .add_sub_option(channel_filter_option("deny", "cmd-configure-channels-deny"))
+                .add_sub_option(channel_filter_option("remove", "cmd-configure-channels-remove"))
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "list", "cmd-configure-channels-list")
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommandGroup, "roles", "cmd-configure-roles")
+                .add_sub_option(role_filter_option("exempt", "cmd-configure-roles-exempt"))
+                .add_sub_option(role_filter_option("only", "cmd-configure-roles-only"))
+                .add_sub_option(role_filter_option("remove", "cmd-configure-roles-remove"))
+                .add_sub_option(
+                    i18n::option(CommandOptionType::SubCommand, "list", "cmd-configure-roles-list")
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "link-mode", "cmd-configure-link-mode")
+                .add_sub_option(mode_option)
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Channel, "channel", "cmd-configure-link-mode-channel")
+                        .channel_types(vec![ChannelType::Text, ChannelType::News, ChannelType::Forum])
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "tag-policy", "cmd-configure-tag-policy")
+                .add_sub_option(policy_option)
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Channel, "mod_channel", "cmd-configure-tag-policy-mod-channel")
+                        .channel_types(vec![ChannelType::Text])
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "hint", "cmd-configure-hint")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::String, "text", "cmd-configure-hint-text")
+                        .max_length(utils::MAX_HINT_LENGTH as u16)
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Integer, "lifetime", "cmd-configure-hint-lifetime")
+                        .min_int_value(*utils::HINT_LIFETIME_RANGE.start())
+                        .max_int_value(*utils::HINT_LIFETIME_RANGE.end())
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Boolean, "reset", "cmd-configure-hint-reset")
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "dedup", "cmd-configure-dedup")
+                .add_sub_option(dedup_option)
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Integer, "window", "cmd-configure-dedup-window")
+                        .min_int_value(*dedup::WINDOW_RANGE_MINS.start())
+                        .max_int_value(*dedup::WINDOW_RANGE_MINS.end())
+                )
+        )
+        .add_option(
+            // The keys are entered in a modal so the secret doesn't end up in the command
+            i18n::option(CommandOptionType::SubCommand, "product-api", "cmd-configure-product-api")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Boolean, "remove", "cmd-configure-product-api-remove")
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "deals-channel", "cmd-configure-deals-channel")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Channel, "channel", "cmd-configure-deals-channel-channel")
+                        .channel_types(vec![ChannelType::Text, ChannelType::News])
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "also-offer", "cmd-configure-also-offer")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::String, "marketplaces", "cmd-configure-also-offer-marketplaces")
+                        .required(true)
+                        .max_length(100)
+                )
+        )
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "language", "cmd-configure-language")
+                .add_sub_option(language_option)
         )
         .dm_permission(false)
         // Nur im Server sichtbar machen:
@@ -36,18 +201,32 @@ pub async fn register_commands(http: &Http) {
     let _ = Command::create_global_command(http, command).await;
 }
 
-/// Handler for `/configure` command - opens modal for selected region.
+/// A `/configure roles` subcommand with a role picker, described by the catalog message `id`
+fn role_filter_option(name: &str, id: &str) -> CreateCommandOption {
+    i18n::option(CommandOptionType::SubCommand, name, id)
+        .add_sub_option(
+            i18n::option(CommandOptionType::Role, "role", "cmd-configure-roles-role")
+                .required(true)
+        )
+}
+
+/// A `/configure channels` subcommand with a channel/category picker, described by the catalog message `id`
+fn channel_filter_option(name: &str, id: &str) -> CreateCommandOption {
+    i18n::option(CommandOptionType::SubCommand, name, id)
+        .add_sub_option(
+            i18n::option(CommandOptionType::Channel, "channel", "cmd-configure-channels-channel")
+                .required(true)
+                .channel_types(vec![ChannelType::Text, ChannelType::News, ChannelType::Forum, ChannelType::Category])
+        )
+}
+
+/// Handler for `/configure` command - checks permissions and dispatches to the subcommand.
 pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     // Ensure this is in a guild
     let guild_id_u64 = if let Some(guild_id) = cmd.guild_id {
         guild_id.get()
     } else {
-        let response = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new()
-                .content("This command can only be used in a server.")
-                .ephemeral(true)
-        );
-        let _ = cmd.create_response(&ctx.http, response).await;
+        respond(ctx, cmd, i18n::text(answer_locale(cmd), "configure-server-only")).await;
         return;
     };
 
@@ -64,38 +243,700 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     };
     let is_owner = guild.owner_id.get() == cmd.user.id.get();
     if !is_owner && !perms.contains(Permissions::ADMINISTRATOR) {
-        let response = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new()
-                .content("You must be a server administrator or the server owner to run this command.")
-                .ephemeral(true)
-        );
-        let _ = cmd.create_response(&ctx.http, response).await;
+        respond(ctx, cmd, i18n::text(answer_locale(cmd), "configure-not-admin")).await;
+        return;
+    }
+
+    let options = cmd.data.options();
+    let Some(ResolvedOption { name, value, .. }) = options.first() else {
         return;
+    };
+    match (*name, value) {
+        ("region", ResolvedValue::SubCommand(sub_options)) => run_region(ctx, cmd, guild_id_u64, sub_options).await,
+        ("network", ResolvedValue::SubCommand(sub_options)) => run_network(ctx, cmd, guild_id_u64, sub_options).await,
+        ("rule", ResolvedValue::SubCommandGroup(group)) => run_rule(ctx, cmd, guild_id_u64, group).await,
+        ("channels", ResolvedValue::SubCommandGroup(group)) => run_channels(ctx, cmd, guild_id_u64, group).await,
+        ("roles", ResolvedValue::SubCommandGroup(group)) => run_roles(ctx, cmd, guild_id_u64, group).await,
+        ("link-mode", ResolvedValue::SubCommand(sub_options)) => run_link_mode(ctx, cmd, guild_id_u64, sub_options).await,
+        ("tag-policy", ResolvedValue::SubCommand(sub_options)) => run_tag_policy(ctx, cmd, guild_id_u64, sub_options).await,
+        ("hint", ResolvedValue::SubCommand(sub_options)) => run_hint(ctx, cmd, guild_id_u64, sub_options).await,
+        ("dedup", ResolvedValue::SubCommand(sub_options)) => run_dedup(ctx, cmd, guild_id_u64, sub_options).await,
+        ("product-api", ResolvedValue::SubCommand(sub_options)) => run_product_api(ctx, cmd, guild_id_u64, sub_options).await,
+        ("deals-channel", ResolvedValue::SubCommand(sub_options)) => run_deals_channel(ctx, cmd, guild_id_u64, sub_options).await,
+        ("also-offer", ResolvedValue::SubCommand(sub_options)) => run_also_offer(ctx, cmd, guild_id_u64, sub_options).await,
+        ("language", ResolvedValue::SubCommand(sub_options)) => run_language(ctx, cmd, guild_id_u64, sub_options).await,
+        _ => {}
     }
+}
+
+/// Reply to a `/configure` invocation with an ephemeral message
+async fn respond(ctx: &Context, cmd: &CommandInteraction, content: impl Into<String>) {
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true)
+    );
+    let _ = cmd.create_response(&ctx.http, response).await;
+}
+
+/// Language of the answer: the guild's configured language, else the member's Discord locale
+fn answer_locale(cmd: &CommandInteraction) -> &'static str {
+    i18n::resolve(cmd.guild_id.map(|id| id.get().to_string()).as_deref(), &cmd.locale)
+}
+
+/// Answer for a failed database write
+fn save_error(locale: &str, error: impl std::fmt::Debug) -> String {
+    i18n::format(locale, "configure-save-error", &[("error", format!("{:?}", error).into())])
+}
 
+/// Find a string option of a subcommand by name
+fn option_str<'a>(options: &[ResolvedOption<'a>], name: &str) -> Option<&'a str> {
+    options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::String(value) if opt.name == name => Some(value),
+        _ => None,
+    })
+}
+
+/// `/configure region` - opens modal for selected region.
+async fn run_region(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
     // Get selected region
-    let region = cmd.data.options.first()
-        .and_then(|opt| opt.value.as_str())
+    let region = option_str(options, "region")
         .unwrap_or("global")
-        .to_string();
+        .trim()
+        .to_lowercase();
+
+    // Only accept "global" or a marketplace with an Associates program
+    if region != "global" && !marketplace::find(&region).is_some_and(|m| m.associates) {
+        respond(ctx, cmd, i18n::format(answer_locale(cmd), "configure-unknown-region", &[("region", region.into())])).await;
+        return;
+    }
 
     // Get current configuration
     let current_config = get_current_config(guild_id_u64);
     let current_footer = get_current_footer(guild_id_u64);
+    let current_params = utils::kept_params(&guild_id_u64.to_string());
     
     // Open configuration modal
-    open_config_modal(ctx, cmd, &region, &current_config, &current_footer).await;
+    open_config_modal(ctx, cmd, &region, &current_config, &current_footer, &current_params, answer_locale(cmd)).await;
+}
+
+/// `/configure network` - sets or removes the tracking ID of a non-Amazon network.
+/// These networks use one ID for every region (see `AffiliateProvider::config_region`).
+async fn run_network(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(provider) = option_str(options, "network").and_then(providers::find) else {
+        return;
+    };
+    let region = provider.config_region("");
+    let tracking_id = option_str(options, "tracking_id").map(str::trim).filter(|id| !id.is_empty());
+
+    if tracking_id.is_some_and(|id| !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        respond(ctx, cmd, i18n::text(locale, "configure-tracking-id-invalid")).await;
+        return;
+    }
+
+    let res = db::with_connection(|conn| match tracking_id {
+        Some(id) => conn.execute(
+            "INSERT OR REPLACE INTO guild_affiliates (guild_id, provider, region, tracking_tag) VALUES (?, ?, ?, ?)",
+            params![guild_id_str, provider.id(), region, id]
+        ),
+        None => conn.execute(
+            "DELETE FROM guild_affiliates WHERE guild_id = ? AND provider = ?",
+            params![guild_id_str, provider.id()]
+        ),
+    });
+
+    let content = match (res, tracking_id) {
+        (Ok(_), Some(id)) => i18n::format(locale, "configure-tracking-id-set", &[
+            ("network", provider.name().into()),
+            ("id", id.into()),
+        ]),
+        (Ok(_), None) => i18n::format(locale, "configure-tracking-id-removed", &[("network", provider.name().into())]),
+        (Err(e), _) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure rule add|list|remove|test` - manages rewrite rules for shops without a built-in provider.
+async fn run_rule(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, group: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(ResolvedOption { name, value: ResolvedValue::SubCommand(options), .. }) = group.first() else {
+        return;
+    };
+
+    let content = match *name {
+        "add" => {
+            let rule = rules::RewriteRule::parse(
+                option_str(options, "host").unwrap_or(""),
+                option_str(options, "strip").unwrap_or(""),
+                option_str(options, "add").unwrap_or(""),
+            );
+            match rule {
+                Ok(rule) => match rules::save(&guild_id_str, &rule) {
+                    Ok(id) => i18n::format(locale, "configure-rule-saved", &[
+                        ("id", id.into()),
+                        ("host", rule.host.as_str().into()),
+                        ("strip", rule.strip_display().into()),
+                        ("add", rule.add_display().into()),
+                    ]),
+                    Err(e) => i18n::format(locale, "configure-rule-save-error", &[("error", e.to_string().into())]),
+                },
+                Err(e) => i18n::format(locale, "configure-rule-invalid", &[("error", e.to_string().into())]),
+            }
+        },
+        "list" => {
+            let rules = rules::for_guild(&guild_id_str);
+            if rules.is_empty() {
+                i18n::text(locale, "configure-rule-list-empty")
+            } else {
+                rules.iter()
+                    .map(|rule| i18n::format(locale, "configure-rule-entry", &[
+                        ("id", rule.id.into()),
+                        ("host", rule.host.as_str().into()),
+                        ("strip", rule.strip_display().into()),
+                        ("add", rule.add_display().into()),
+                    ]))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        },
+        "remove" => {
+            let id = options.iter().find_map(|opt| match opt.value {
+                ResolvedValue::Integer(id) if opt.name == "id" => Some(id),
+                _ => None,
+            });
+            match id.map(|id| rules::remove(&guild_id_str, id)) {
+                Some(Ok(true)) => i18n::text(locale, "configure-rule-removed"),
+                Some(Ok(false)) | None => i18n::text(locale, "configure-rule-not-found"),
+                Some(Err(e)) => i18n::format(locale, "configure-rule-remove-error", &[("error", format!("{:?}", e).into())]),
+            }
+        },
+        "test" => {
+            let sample = option_str(options, "url").unwrap_or("").trim();
+            let sample = if sample.contains("://") { sample.to_string() } else { format!("https://{}", sample) };
+            let rules = rules::for_guild(&guild_id_str);
+            if utils::is_affiliate_url(&sample) {
+                i18n::text(locale, "configure-rule-builtin")
+            } else {
+                match rules::matching(&rules, &sample) {
+                    Some(rule) => match url::Url::parse(&sample) {
+                        Ok(url) => i18n::format(locale, "configure-rule-matches", &[
+                            ("id", rule.id.into()),
+                            ("host", rule.host.as_str().into()),
+                            ("url", rule.apply(&url).to_string().into()),
+                        ]),
+                        Err(_) => i18n::text(locale, "configure-rule-invalid-url"),
+                    },
+                    None => i18n::text(locale, "configure-rule-no-match"),
+                }
+            }
+        },
+        _ => return,
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure channels allow|deny|remove|list` - manages where the message handler processes links.
+async fn run_channels(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, group: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(ResolvedOption { name, value: ResolvedValue::SubCommand(options), .. }) = group.first() else {
+        return;
+    };
+    let channel = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Channel(channel) if opt.name == "channel" => Some(channel.id),
+        _ => None,
+    });
+
+    let content = match (*name, channel) {
+        ("list", _) => {
+            let filters = utils::channel_filters(&guild_id_str);
+            let list = |filter: utils::ChannelFilter| {
+                let channels: Vec<String> = filters.iter()
+                    .filter(|(_, f)| *f == filter)
+                    .map(|(id, _)| format!("<#{}>", id))
+                    .collect();
+                if channels.is_empty() { "—".to_string() } else { channels.join(", ") }
+            };
+            i18n::format(locale, "configure-channels-list", &[
+                ("allowed", list(utils::ChannelFilter::Allow).into()),
+                ("denied", list(utils::ChannelFilter::Deny).into()),
+            ])
+        },
+        ("remove", Some(channel)) => {
+            let res = db::with_connection(|conn| {
+                conn.execute(
+                    "DELETE FROM guild_channel_filters WHERE guild_id = ? AND channel_id = ?",
+                    params![guild_id_str, channel.get().to_string()]
+                )
+            });
+            match res {
+                Ok(0) => i18n::format(locale, "configure-not-listed", &[("target", channel.mention().to_string().into())]),
+                Ok(_) => i18n::format(locale, "configure-unlisted", &[("target", channel.mention().to_string().into())]),
+                Err(e) => save_error(locale, e),
+            }
+        },
+        (filter, Some(channel)) => {
+            let Some(filter) = utils::ChannelFilter::parse(filter) else {
+                return;
+            };
+            let res = db::with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO guild_channel_filters (guild_id, channel_id, mode) VALUES (?, ?, ?)
+                     ON CONFLICT(guild_id, channel_id) DO UPDATE SET mode = excluded.mode",
+                    params![guild_id_str, channel.get().to_string(), filter.as_str()]
+                )
+            });
+            match (res, filter) {
+                (Ok(_), utils::ChannelFilter::Allow) => {
+                    i18n::format(locale, "configure-channel-allowed", &[("channel", channel.mention().to_string().into())])
+                },
+                (Ok(_), utils::ChannelFilter::Deny) => {
+                    i18n::format(locale, "configure-channel-denied", &[("channel", channel.mention().to_string().into())])
+                },
+                (Err(e), _) => save_error(locale, e),
+            }
+        },
+        _ => return,
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure roles exempt|only|remove|list` - manages whose links the message handler processes.
+async fn run_roles(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, group: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(ResolvedOption { name, value: ResolvedValue::SubCommand(options), .. }) = group.first() else {
+        return;
+    };
+    let role = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Role(role) if opt.name == "role" => Some(role.id),
+        _ => None,
+    });
+
+    let content = match (*name, role) {
+        ("list", _) => {
+            let filters = utils::role_filters(&guild_id_str);
+            let list = |filter: utils::RoleFilter| {
+                let roles: Vec<String> = filters.iter()
+                    .filter(|(_, f)| *f == filter)
+                    .map(|(id, _)| format!("<@&{}>", id))
+                    .collect();
+                if roles.is_empty() { "—".to_string() } else { roles.join(", ") }
+            };
+            i18n::format(locale, "configure-roles-list", &[
+                ("exempt", list(utils::RoleFilter::Exempt).into()),
+                ("only", list(utils::RoleFilter::Only).into()),
+            ])
+        },
+        ("remove", Some(role)) => {
+            let res = db::with_connection(|conn| {
+                conn.execute(
+                    "DELETE FROM guild_role_filters WHERE guild_id = ? AND role_id = ?",
+                    params![guild_id_str, role.get().to_string()]
+                )
+            });
+            match res {
+                Ok(0) => i18n::format(locale, "configure-not-listed", &[("target", role.mention().to_string().into())]),
+                Ok(_) => i18n::format(locale, "configure-unlisted", &[("target", role.mention().to_string().into())]),
+                Err(e) => save_error(locale, e),
+            }
+        },
+        (filter, Some(role)) => {
+            let Some(filter) = utils::RoleFilter::parse(filter) else {
+                return;
+            };
+            let res = db::with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO guild_role_filters (guild_id, role_id, mode) VALUES (?, ?, ?)
+                     ON CONFLICT(guild_id, role_id) DO UPDATE SET mode = excluded.mode",
+                    params![guild_id_str, role.get().to_string(), filter.as_str()]
+                )
+            });
+            match (res, filter) {
+                (Ok(_), utils::RoleFilter::Exempt) => {
+                    i18n::format(locale, "configure-role-exempt", &[("role", role.mention().to_string().into())])
+                },
+                (Ok(_), utils::RoleFilter::Only) => {
+                    i18n::format(locale, "configure-role-only", &[("role", role.mention().to_string().into())])
+                },
+                (Err(e), _) => save_error(locale, e),
+            }
+        },
+        _ => return,
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// Catalog message of the choice label for a link mode
+fn link_mode_label(mode: utils::LinkMode) -> &'static str {
+    match mode {
+        utils::LinkMode::DeleteAndHint => "link-mode-delete-and-hint",
+        utils::LinkMode::ButtonReply => "link-mode-button-reply",
+        utils::LinkMode::TextReply => "link-mode-text-reply",
+        utils::LinkMode::SuppressEmbedsAndReply => "link-mode-suppress-embeds-and-reply",
+        utils::LinkMode::WebhookRepost => "link-mode-webhook-repost",
+        utils::LinkMode::Off => "link-mode-off",
+    }
+}
+
+/// `/configure link-mode` - sets the link mode of the server or of a single channel.
+async fn run_link_mode(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(value) = option_str(options, "mode") else {
+        return;
+    };
+    // "reset" clears the setting so the channel falls back to the server, and the server to the default
+    let mode = utils::LinkMode::parse(value);
+    let channel = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Channel(channel) if opt.name == "channel" => Some(channel.id),
+        _ => None,
+    });
+
+    let stored = mode.map(|m| m.as_str());
+    let res = match channel {
+        Some(channel) => db::set_channel_setting(&guild_id_str, &channel.get().to_string(), "link_mode", stored),
+        None => db::set_guild_setting(&guild_id_str, "link_mode", stored),
+    };
+
+    let content = match (res, mode, channel) {
+        (Ok(_), Some(mode), Some(channel)) => i18n::format(locale, "configure-link-mode-channel", &[
+            ("channel", channel.mention().to_string().into()),
+            ("mode", i18n::text(locale, link_mode_label(mode)).into()),
+        ]),
+        (Ok(_), Some(mode), None) => i18n::format(locale, "configure-link-mode-server", &[
+            ("mode", i18n::text(locale, link_mode_label(mode)).into()),
+        ]),
+        (Ok(_), None, Some(channel)) => i18n::format(locale, "configure-link-mode-channel-reset", &[
+            ("channel", channel.mention().to_string().into()),
+        ]),
+        (Ok(_), None, None) => i18n::format(locale, "configure-link-mode-server-reset", &[
+            ("mode", i18n::text(locale, link_mode_label(utils::LinkMode::DeleteAndHint)).into()),
+        ]),
+        (Err(e), _, _) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// Catalog message of the choice label for a foreign tag policy
+fn policy_label(policy: utils::ForeignTagPolicy) -> &'static str {
+    match policy {
+        utils::ForeignTagPolicy::Replace => "tag-policy-replace",
+        utils::ForeignTagPolicy::Keep => "tag-policy-keep",
+        utils::ForeignTagPolicy::Reject => "tag-policy-reject",
+        utils::ForeignTagPolicy::Flag => "tag-policy-flag",
+    }
+}
+
+/// `/configure tag-policy` - sets how links with existing affiliate tags are handled.
+async fn run_tag_policy(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(policy) = option_str(options, "policy").and_then(utils::ForeignTagPolicy::parse) else {
+        return;
+    };
+    let mod_channel = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Channel(channel) if opt.name == "mod_channel" => Some(channel.id),
+        _ => None,
+    });
+
+    // Flagging needs somewhere to report to
+    let has_mod_channel = mod_channel.is_some() || db::guild_setting(&guild_id_str, "mod_channel_id").is_some();
+    if policy == utils::ForeignTagPolicy::Flag && !has_mod_channel {
+        respond(ctx, cmd, i18n::text(locale, "configure-tag-policy-needs-channel")).await;
+        return;
+    }
+
+    let res = db::set_guild_setting(&guild_id_str, "foreign_tag_policy", Some(policy.as_str()))
+        .and_then(|_| match mod_channel {
+            Some(channel) => db::set_guild_setting(&guild_id_str, "mod_channel_id", Some(&channel.get().to_string())),
+            None => Ok(()),
+        });
+
+    let content = match res {
+        Ok(_) => match mod_channel {
+            Some(channel) => i18n::format(locale, "configure-tag-policy-set-with-channel", &[
+                ("policy", i18n::text(locale, policy_label(policy)).into()),
+                ("channel", channel.mention().to_string().into()),
+            ]),
+            None => i18n::format(locale, "configure-tag-policy-set", &[
+                ("policy", i18n::text(locale, policy_label(policy)).into()),
+            ]),
+        },
+        Err(e) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure hint` - sets the hint text and lifetime; without options it shows the current hint.
+async fn run_hint(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let text = option_str(options, "text").map(str::trim).filter(|text| !text.is_empty());
+    let lifetime = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Integer(secs) if opt.name == "lifetime" => Some(secs),
+        _ => None,
+    });
+    let reset = options.iter().any(|opt| opt.name == "reset" && matches!(opt.value, ResolvedValue::Boolean(true)));
+    let changed = text.is_some() || lifetime.is_some();
+
+    let res = if reset {
+        db::set_guild_setting(&guild_id_str, "hint_text", None)
+            .and_then(|_| db::set_guild_setting(&guild_id_str, "hint_lifetime_secs", None))
+    } else {
+        let text_res = match text {
+            Some(text) => db::set_guild_setting(&guild_id_str, "hint_text", Some(text)),
+            None => Ok(()),
+        };
+        text_res.and_then(|_| match lifetime {
+            Some(secs) => db::set_guild_setting(&guild_id_str, "hint_lifetime_secs", Some(&secs.to_string())),
+            None => Ok(()),
+        })
+    };
+
+    // The preview is in the language the channel sees: the guild's language, else its Discord locale
+    let guild_locale = cmd.guild_locale.as_deref().unwrap_or_default();
+    let preview = utils::guild_hint(&guild_id_str, guild_locale, &cmd.user.id.mention().to_string());
+    let args = [
+        ("hint", preview.into()),
+        ("seconds", utils::hint_lifetime(&guild_id_str).as_secs().into()),
+    ];
+    let content = match res {
+        Ok(_) if reset => i18n::format(locale, "configure-hint-reset", &args),
+        Ok(_) if !changed => i18n::format(locale, "configure-hint-current", &args),
+        Ok(_) => i18n::format(locale, "configure-hint-saved", &args),
+        Err(e) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// Catalog message of the choice label for a dedup mode
+fn dedup_mode_label(mode: dedup::DedupMode) -> &'static str {
+    match mode {
+        dedup::DedupMode::Off => "dedup-mode-off",
+        dedup::DedupMode::React => "dedup-mode-react",
+        dedup::DedupMode::JumpLink => "dedup-mode-jump-link",
+        dedup::DedupMode::Silent => "dedup-mode-silent",
+    }
+}
+
+/// `/configure dedup` - sets how repeated products are handled and for how long.
+async fn run_dedup(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let Some(mode) = option_str(options, "mode").and_then(dedup::DedupMode::parse) else {
+        return;
+    };
+    let window = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Integer(mins) if opt.name == "window" => Some(mins),
+        _ => None,
+    });
+
+    let res = db::set_guild_setting(&guild_id_str, "dedup_mode", Some(mode.as_str()))
+        .and_then(|_| match window {
+            Some(mins) => db::set_guild_setting(&guild_id_str, "dedup_window_mins", Some(&mins.to_string())),
+            None => Ok(()),
+        });
+
+    let content = match res {
+        Ok(_) if mode == dedup::DedupMode::Off => i18n::format(locale, "configure-dedup-set", &[
+            ("mode", i18n::text(locale, dedup_mode_label(mode)).into()),
+        ]),
+        Ok(_) => i18n::format(locale, "configure-dedup-set-with-window", &[
+            ("mode", i18n::text(locale, dedup_mode_label(mode)).into()),
+            ("minutes", (dedup::window(&guild_id_str).as_secs() / 60).into()),
+        ]),
+        Err(e) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure product-api` - opens a modal for the guild's Product Advertising API keys; `remove` deletes them.
+/// Keys are stored unencrypted in the bot's database.
+async fn run_product_api(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let remove = options.iter().any(|opt| opt.name == "remove" && matches!(opt.value, ResolvedValue::Boolean(true)));
+    if remove {
+        let content = match paapi::set_guild_credentials(&guild_id_str, None) {
+            Ok(_) => i18n::text(locale, "configure-product-api-removed"),
+            Err(e) => save_error(locale, e),
+        };
+        respond(ctx, cmd, content).await;
+        return;
+    }
+
+    // The access key and partner tag are pre-filled; the secret key is never sent back to Discord
+    let current = paapi::guild_credentials(&guild_id_str);
+    let (access_key, partner_tag) = current.clone().unwrap_or_default();
+    let mut secret_key = CreateInputText::new(
+        InputTextStyle::Short,
+        i18n::text(locale, "configure-product-api-modal-secret-key"),
+        "secret_key"
+    )
+    .max_length(128)
+    .required(current.is_none());
+    if current.is_some() {
+        secret_key = secret_key.placeholder(i18n::text(locale, "configure-product-api-modal-secret-keep"));
+    }
+
+    let modal = CreateModal::new(PRODUCT_API_MODAL, i18n::text(locale, "configure-product-api-modal-title"))
+        .components(vec![
+            CreateActionRow::InputText(
+                CreateInputText::new(
+                    InputTextStyle::Short,
+                    i18n::text(locale, "configure-product-api-modal-access-key"),
+                    "access_key"
+                )
+                .max_length(128)
+                .required(true)
+                .value(access_key)
+            ),
+            CreateActionRow::InputText(secret_key),
+            CreateActionRow::InputText(
+                CreateInputText::new(
+                    InputTextStyle::Short,
+                    i18n::text(locale, "configure-product-api-modal-partner-tag"),
+                    "partner_tag"
+                )
+                .placeholder(i18n::text(locale, "configure-product-api-modal-partner-tag-placeholder"))
+                .max_length(50)
+                .required(false)
+                .value(partner_tag.unwrap_or_default())
+            ),
+        ]);
+    let _ = cmd.create_response(&ctx.http, CreateInteractionResponse::Modal(modal)).await;
+}
+
+/// Handle the submitted `/configure product-api` modal
+pub async fn handle_product_api_modal(ctx: &Context, modal: &Interaction) {
+    let Interaction::Modal(modal_submit) = modal else {
+        return;
+    };
+    let Some(guild_id) = modal_submit.guild_id else {
+        return;
+    };
+    let guild_id_str = guild_id.get().to_string();
+    let locale = i18n::resolve(Some(&guild_id_str), &modal_submit.locale);
+
+    let field = |name: &str| {
+        modal_submit.data.components.iter()
+            .flat_map(|row| &row.components)
+            .find_map(|component| match component {
+                ActionRowComponent::InputText(input) if input.custom_id == name => input.value.as_deref(),
+                _ => None,
+            })
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let access_key = field("access_key");
+    let secret_key = field("secret_key");
+    let partner_tag = field("partner_tag");
+
+    // A new access key always needs its secret key
+    let has_secret = secret_key.is_some() || paapi::guild_credentials(&guild_id_str)
+        .is_some_and(|(current, _)| Some(current.as_str()) == access_key);
+    let content = match access_key {
+        Some(access_key) if has_secret => {
+            match paapi::set_guild_credentials(&guild_id_str, Some((access_key, secret_key, partner_tag))) {
+                Ok(_) => i18n::text(locale, "configure-product-api-saved"),
+                Err(e) => save_error(locale, e),
+            }
+        },
+        _ => i18n::text(locale, "configure-product-api-incomplete"),
+    };
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true)
+    );
+    let _ = modal_submit.create_response(&ctx.http, response).await;
+}
+
+/// `/configure deals-channel` - sets the channel for price watch alerts; without a channel alerts go by DM.
+async fn run_deals_channel(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let channel = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Channel(channel) if opt.name == "channel" => Some(channel.id),
+        _ => None,
+    });
+
+    let stored = channel.map(|channel| channel.get().to_string());
+    let content = match (db::set_guild_setting(&guild_id_str, "deals_channel_id", stored.as_deref()), channel) {
+        (Ok(_), Some(channel)) => {
+            i18n::format(locale, "configure-deals-channel-set", &[("channel", channel.mention().to_string().into())])
+        },
+        (Ok(_), None) => i18n::text(locale, "configure-deals-channel-dm"),
+        (Err(e), _) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure also-offer` - sets the marketplaces offered next to the posted one.
+async fn run_also_offer(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let locale = answer_locale(cmd);
+    let input = option_str(options, "marketplaces").unwrap_or("").trim().to_lowercase();
+    let input = if input == "none" { String::new() } else { input };
+
+    let marketplaces = match utils::parse_marketplaces(&input) {
+        Ok(marketplaces) => marketplaces,
+        Err(unknown) => {
+            respond(ctx, cmd, i18n::format(locale, "configure-also-offer-unknown", &[("marketplace", unknown.into())])).await;
+            return;
+        },
+    };
+    if marketplaces.len() > utils::MAX_ALSO_OFFER {
+        respond(ctx, cmd, i18n::format(locale, "configure-also-offer-too-many", &[("max", utils::MAX_ALSO_OFFER.into())])).await;
+        return;
+    }
+
+    let stored = (!marketplaces.is_empty()).then(|| marketplaces.iter().map(|m| m.tld).collect::<Vec<_>>().join(","));
+    let content = match db::set_guild_setting(&guild_id_str, "also_offer", stored.as_deref()) {
+        Ok(_) if marketplaces.is_empty() => i18n::text(locale, "configure-also-offer-off"),
+        Ok(_) => i18n::format(locale, "configure-also-offer-set", &[
+            ("marketplaces", marketplaces.iter().map(|m| m.label()).collect::<Vec<_>>().join(", ").into()),
+        ]),
+        Err(e) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
+}
+
+/// `/configure language` - sets the language of the bot's messages; "auto" follows each member's Discord language.
+async fn run_language(ctx: &Context, cmd: &CommandInteraction, guild_id_u64: u64, options: &[ResolvedOption<'_>]) {
+    let guild_id_str = guild_id_u64.to_string();
+    let Some(choice) = option_str(options, "language") else {
+        return;
+    };
+    let language = i18n::supported(choice);
+
+    let res = db::set_guild_setting(&guild_id_str, "locale", language);
+    // Answered in the new language
+    let locale = answer_locale(cmd);
+    let content = match (res, language) {
+        (Ok(_), Some(language)) => {
+            i18n::format(locale, "configure-language-set", &[("language", i18n::text(language, "language-name").into())])
+        },
+        (Ok(_), None) => i18n::text(locale, "configure-language-auto"),
+        (Err(e), _) => save_error(locale, e),
+    };
+    respond(ctx, cmd, content).await;
 }
 
 /// Handle autocomplete for region selection  
 pub async fn handle_autocomplete(ctx: &Context, autocomplete: &Interaction) {
     if let Interaction::Autocomplete(auto) = autocomplete {
         // Find the focused option (the one being typed)
-        let input = auto.data.options.first()
-            .and_then(|opt| opt.value.as_str())
+        let input = auto.data.autocomplete()
+            .map(|opt| opt.value)
             .unwrap_or("");
         
-        let suggestions = get_region_suggestions(input);
+        let locale = i18n::resolve(auto.guild_id.map(|id| id.get().to_string()).as_deref(), &auto.locale);
+        let suggestions = get_region_suggestions(input, locale);
         
         let mut response = CreateAutocompleteResponse::new();
         for (value, name) in suggestions.into_iter().take(25) {
@@ -108,37 +949,26 @@ pub async fn handle_autocomplete(ctx: &Context, autocomplete: &Interaction) {
 }
 
 /// Get region suggestions for autocomplete
-fn get_region_suggestions(input: &str) -> Vec<(String, String)> {
-    let regions = vec![
-        ("global".to_string(), "🌍 Global Settings (All Regions)".to_string()),
-        ("com".to_string(), "🇺🇸 USA (amazon.com)".to_string()),
-        ("ca".to_string(), "🇨🇦 Canada (amazon.ca)".to_string()),
-        ("com.mx".to_string(), "🇲🇽 Mexico (amazon.com.mx)".to_string()),
-        ("br".to_string(), "🇧🇷 Brazil (amazon.br)".to_string()),
-        ("co.uk".to_string(), "🇬🇧 UK (amazon.co.uk)".to_string()),
-        ("de".to_string(), "🇩🇪 Germany (amazon.de)".to_string()),
-        ("fr".to_string(), "🇫🇷 France (amazon.fr)".to_string()),
-        ("es".to_string(), "🇪🇸 Spain (amazon.es)".to_string()),
-        ("it".to_string(), "🇮🇹 Italy (amazon.it)".to_string()),
-        ("nl".to_string(), "🇳🇱 Netherlands (amazon.nl)".to_string()),
-        ("se".to_string(), "🇸🇪 Sweden (amazon.se)".to_string()),
-        ("pl".to_string(), "🇵🇱 Poland (amazon.pl)".to_string()),
-        ("ae".to_string(), "🇦🇪 UAE (amazon.ae)".to_string()),
-        ("sa".to_string(), "🇸🇦 Saudi Arabia (amazon.sa)".to_string()),
-        ("in".to_string(), "🇮🇳 India (amazon.in)".to_string()),
-        ("co.jp".to_string(), "🇯🇵 Japan (amazon.co.jp)".to_string()),
-        ("sg".to_string(), "🇸🇬 Singapore (amazon.sg)".to_string()),
-        ("cn".to_string(), "🇨🇳 China (amazon.cn)".to_string()),
-        ("com.au".to_string(), "🇦🇺 Australia (amazon.com.au)".to_string()),
-    ];
-    
+fn get_region_suggestions(input: &str, locale: &str) -> Vec<(String, String)> {
     let input_lower = input.to_lowercase();
-    regions.into_iter()
-        .filter(|(code, name)| {
-            code.contains(&input_lower) || name.to_lowercase().contains(&input_lower)
-        })
-        .take(25)
-        .collect()
+    let matches = |code: &str, name: &str| {
+        code.contains(&input_lower) || name.to_lowercase().contains(&input_lower)
+    };
+
+    let mut regions = Vec::new();
+    let global = i18n::text(locale, "configure-global-region");
+    if matches("global", &global) {
+        regions.push(("global".to_string(), global));
+    }
+    // Match on TLD, display label or ISO country code ("us", "gb", ...)
+    regions.extend(
+        marketplace::MARKETPLACES.iter()
+            .filter(|m| m.associates)
+            .filter(|m| matches(m.tld, &m.label()) || m.country.eq_ignore_ascii_case(&input_lower))
+            .map(|m| (m.tld.to_string(), m.label()))
+    );
+    regions.truncate(25);
+    regions
 }
 
 /// Open configuration modal for selected region
@@ -147,13 +977,16 @@ async fn open_config_modal(
     cmd: &CommandInteraction,
     region: &str,
     current_config: &std::collections::HashMap<String, String>,
-    current_footer: &Option<String>
+    current_footer: &Option<String>,
+    current_params: &[String],
+    locale: &str,
 ) {
-    let modal_title = if region == "global" {
-        "🌍 Global Amazon Configuration".to_string()
-    } else {
-        format!("🌍 Configure Amazon {}", region.to_uppercase())
+    let market = marketplace::find(region);
+    let modal_title = match market {
+        Some(m) => i18n::format(locale, "configure-modal-title", &[("flag", m.flag.into()), ("domain", m.domain().into())]),
+        None => i18n::text(locale, "configure-modal-title-global"),
     };
+    let tag_suffix = market.and_then(|m| m.tag_suffix).unwrap_or("-20");
     
     let current_tag = current_config.get(region).cloned().unwrap_or_default();
     let current_footer_text = current_footer.clone().unwrap_or_default();
@@ -166,10 +999,10 @@ async fn open_config_modal(
         CreateActionRow::InputText(
             CreateInputText::new(
                 InputTextStyle::Short,
-                format!("🏷️ Tracking Tag for {}", region.to_uppercase()),
+                i18n::format(locale, "configure-modal-tag", &[("region", region.to_uppercase().into())]),
                 "tracking_tag"
             )
-            .placeholder(format!("your-tag-{}", if region.contains("co.") { "21" } else { "20" }))
+            .placeholder(format!("your-tag{}", tag_suffix))
             .max_length(50)
             .required(false)
             .value(&current_tag)
@@ -177,13 +1010,24 @@ async fn open_config_modal(
         CreateActionRow::InputText(
             CreateInputText::new(
                 InputTextStyle::Paragraph,
-                "💬 Custom Footer (optional)",
+                i18n::text(locale, "configure-modal-footer"),
                 "footer_text"
             )
-            .placeholder("{{sender}} recommended this and supports our server!")
+            .placeholder(i18n::text(locale, "configure-modal-footer-placeholder"))
             .max_length(500)
             .required(false)
             .value(&current_footer_text)
+        ),
+        CreateActionRow::InputText(
+            CreateInputText::new(
+                InputTextStyle::Short,
+                i18n::text(locale, "configure-modal-params"),
+                "kept_params"
+            )
+            .placeholder("th, psc, smid")
+            .max_length(200)
+            .required(false)
+            .value(current_params.join(", "))
         )
     ]);
     
@@ -199,15 +1043,13 @@ fn get_current_config(guild_id: u64) -> std::collections::HashMap<String, String
     
     // Get all tracking tags for this guild
     let _ = db::with_connection(|conn| {
-        let mut stmt = conn.prepare("SELECT region, tracking_tag FROM guild_affiliates WHERE guild_id = ?")?;
+        let mut stmt = conn.prepare("SELECT region, tracking_tag FROM guild_affiliates WHERE guild_id = ? AND provider = 'amazon'")?;
         let rows = stmt.query_map(params![guild_id_str], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?;
         
-        for row in rows {
-            if let Ok((region, tag)) = row {
-                config.insert(region, tag);
-            }
+        for (region, tag) in rows.flatten() {
+            config.insert(region, tag);
         }
         
         Ok(())
@@ -223,7 +1065,7 @@ fn get_current_footer(guild_id: u64) -> Option<String> {
     
     db::with_connection(|conn| {
         Ok(conn.query_row(
-            "SELECT footer_text FROM guild_settings WHERE guild_id = ?",
+            "SELECT footer_text FROM guild_settings WHERE guild_id = ? AND footer_text != ''",
             params![guild_id_str],
             |r| r.get::<_, String>(0),
         ).ok())
@@ -245,10 +1087,12 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
             .to_string();
         
         let guild_id_str = guild_id.to_string();
+        let locale = i18n::resolve(Some(&guild_id_str), &modal_submit.locale);
         
         // Extract form data
         let mut tracking_tag = None;
         let mut footer_text = None;
+        let mut kept_params = Vec::new();
         
         for action_row in &modal_submit.data.components {
             for component in &action_row.components {
@@ -270,6 +1114,11 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
                                 }
                             }
                         },
+                        "kept_params" => {
+                            if let Some(value) = &input.value {
+                                kept_params = parse_param_list(value);
+                            }
+                        },
                         _ => {}
                     }
                 }
@@ -284,14 +1133,14 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
         if let Some(tag) = tracking_tag {
             if region == "global" {
                 // Update all existing regions with the same tag
-                let mut stmt = conn.prepare("SELECT DISTINCT region FROM guild_affiliates WHERE guild_id = ?")?;
+                let mut stmt = conn.prepare("SELECT DISTINCT region FROM guild_affiliates WHERE guild_id = ? AND provider = 'amazon'")?;
                 let regions: Vec<String> = stmt.query_map(params![guild_id_str], |row| {
-                    Ok(row.get::<_, String>(0)?)
+                    row.get::<_, String>(0)
                 })?.collect::<Result<Vec<_>, _>>()?;
                 
                 for r in regions {
                     conn.execute(
-                        "UPDATE guild_affiliates SET tracking_tag = ? WHERE guild_id = ? AND region = ?",
+                        "UPDATE guild_affiliates SET tracking_tag = ? WHERE guild_id = ? AND provider = 'amazon' AND region = ?",
                         params![tag, guild_id_str, r]
                     )?;
                     updates += 1;
@@ -301,7 +1150,7 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
                     // No existing regions, add popular ones
                     for default_region in ["com", "de", "co.uk", "fr"] {
                         conn.execute(
-                            "INSERT OR REPLACE INTO guild_affiliates (guild_id, region, tracking_tag) VALUES (?, ?, ?)",
+                            "INSERT OR REPLACE INTO guild_affiliates (guild_id, provider, region, tracking_tag) VALUES (?, 'amazon', ?, ?)",
                             params![guild_id_str, default_region, tag]
                         )?;
                         updates += 1;
@@ -309,7 +1158,7 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
                 }
             } else {
                 conn.execute(
-                    "INSERT OR REPLACE INTO guild_affiliates (guild_id, region, tracking_tag) VALUES (?, ?, ?)",
+                    "INSERT OR REPLACE INTO guild_affiliates (guild_id, provider, region, tracking_tag) VALUES (?, 'amazon', ?, ?)",
                     params![guild_id_str, region, tag]
                 )?;
                 updates += 1;
@@ -319,12 +1168,26 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
         // Handle footer
         if let Some(footer) = footer_text {
             conn.execute(
-                "INSERT OR REPLACE INTO guild_settings (guild_id, footer_text) VALUES (?, ?)",
+                "INSERT INTO guild_settings (guild_id, footer_text) VALUES (?, ?)
+                 ON CONFLICT(guild_id) DO UPDATE SET footer_text = excluded.footer_text",
                 params![guild_id_str, footer]
             )?;
             updates += 1;
         }
         
+        // Handle kept URL parameters: the field is pre-filled, so the submitted list replaces the old one
+        let current_params = utils::kept_params(&guild_id_str);
+        if kept_params != current_params {
+            conn.execute("DELETE FROM guild_kept_params WHERE guild_id = ?", params![guild_id_str])?;
+            for param in &kept_params {
+                conn.execute(
+                    "INSERT OR IGNORE INTO guild_kept_params (guild_id, param) VALUES (?, ?)",
+                    params![guild_id_str, param]
+                )?;
+            }
+            updates += 1;
+        }
+        
         Ok(updates)
     });
     
@@ -332,13 +1195,16 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
     let content = match res {
         Ok(updates) if updates > 0 => {
             if region == "global" {
-                format!("✅ Global configuration updated!\n🌍 {} regions configured", updates)
+                i18n::format(locale, "configure-updated-global", &[("count", updates.into())])
             } else {
-                format!("✅ Configuration updated for {}!\n🌍 {} items configured", region.to_uppercase(), updates)
+                i18n::format(locale, "configure-updated", &[
+                    ("region", region.to_uppercase().into()),
+                    ("count", updates.into()),
+                ])
             }
         },
-        Ok(_) => "ℹ️ No changes made.".to_string(),
-        Err(e) => format!("❌ Error saving configuration: {:?}", e),
+        Ok(_) => i18n::text(locale, "configure-no-changes"),
+        Err(e) => save_error(locale, e),
     };
     
         let response = CreateInteractionResponse::Message(
@@ -349,3 +1215,18 @@ pub async fn handle_modal(ctx: &Context, modal: &Interaction) {
         let _ = modal_submit.create_response(&ctx.http, response).await;
     }
 }
+
+/// Parse a comma/space separated list of query parameter names.
+/// Names are lowercased, sorted and deduplicated; `tag` and invalid names are ignored.
+fn parse_param_list(input: &str) -> Vec<String> {
+    let mut params: Vec<String> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty() && p != "tag")
+        .filter(|p| p.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .collect();
+    params.sort();
+    params.dedup();
+    params.truncate(20);
+    params
+}
@@ -1,18 +1,17 @@
 // src/commands/stats.rs
 use serenity::all::{
     Command, CommandInteraction, 
-    CreateCommand, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
     CreateEmbed, Colour,
     InstallationContext, InteractionContext,
 };
 use serenity::http::Http;
 use serenity::prelude::*;
 use rusqlite::params;
-use super::super::db;
+use super::super::{clicks, db, i18n, providers, rules};
 
 pub async fn register_commands(http: &Http) {
-    let command = CreateCommand::new("stats")
-        .description("Show link generation statistics")
+    let command = i18n::command("stats", "cmd-stats")
         .dm_permission(false)
         // Nur im Server sichtbar machen:
         .integration_types(vec![InstallationContext::Guild])
@@ -23,26 +22,35 @@ pub async fn register_commands(http: &Http) {
 
 pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     let guild_id = cmd.guild_id.unwrap().get().to_string();
-    let (global_count, guild_count, top_regions) = match db::with_connection(|conn| {
+    let locale = i18n::resolve(Some(&guild_id), &cmd.locale);
+    let (global_count, guild_count, global_clicks, guild_clicks, top_regions) = match db::with_connection(|conn| {
         let global: i64 = conn.query_row("SELECT COUNT(*) FROM link_stats", [], |r| r.get(0))?;
         let local: i64 = conn.query_row("SELECT COUNT(*) FROM link_stats WHERE guild_id = ?", params![guild_id], |r| r.get(0))?;
+        // Older clicks are only deleted on startup
+        let since = chrono::Utc::now().timestamp() - clicks::CLICK_RETENTION_DAYS * 24 * 60 * 60;
+        let global_clicks: i64 = conn.query_row("SELECT COUNT(*) FROM clicks WHERE clicked_at >= ?", params![since], |r| r.get(0))?;
+        let local_clicks: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM clicks WHERE guild_id = ? AND clicked_at >= ?",
+            params![guild_id, since],
+            |r| r.get(0),
+        )?;
         
         // Get top 5 regions for this server
         let mut stmt = conn.prepare(
-            "SELECT region, COUNT(*) as count FROM link_stats WHERE guild_id = ? GROUP BY region ORDER BY count DESC LIMIT 5"
+            "SELECT provider, region, COUNT(*) as count FROM link_stats WHERE guild_id = ? GROUP BY provider, region ORDER BY count DESC LIMIT 5"
         )?;
-        let regions: Vec<(String, i64)> = stmt.query_map(params![guild_id], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        let regions: Vec<(String, String, i64)> = stmt.query_map(params![guild_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
         })?.collect::<Result<Vec<_>, _>>()?;
         
-        Ok((global, local, regions))
+        Ok((global, local, global_clicks, local_clicks, regions))
     }) {
         Ok(data) => data,
         Err(e) => {
             eprintln!("Database error in stats command: {}", e);
             let error_response = CreateInteractionResponse::Message(
                 CreateInteractionResponseMessage::new()
-                    .content("❌ Unable to fetch statistics. Please try again later.")
+                    .content(i18n::text(locale, "stats-error"))
                     .ephemeral(true)
             );
             let _ = cmd.create_response(&ctx.http, error_response).await;
@@ -51,23 +59,43 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
     };
 
     // Build top regions field
+    let links = |count: i64| i18n::format(locale, "stats-links", &[("count", count.into())]);
+    // Clicks below the links once click tracking is on
+    let totals = |links_count: i64, clicks_count: i64| {
+        if clicks::enabled() {
+            format!("{}\n{}", links(links_count), i18n::format(locale, "stats-clicks", &[
+                ("count", clicks_count.into()),
+                ("days", clicks::CLICK_RETENTION_DAYS.into()),
+            ]))
+        } else {
+            links(links_count)
+        }
+    };
     let regions_text = if top_regions.is_empty() {
-        "No regions yet".to_string()
+        i18n::text(locale, "stats-no-regions")
     } else {
         top_regions.iter()
-            .map(|(region, count)| format!("🌍 **{}**: {} links", region.to_uppercase(), count))
+            .map(|(provider, region, count)| match providers::find(provider) {
+                Some(p) => format!("{}: {}", p.region_label(region), links(*count)),
+                None if provider == rules::NETWORK_ID => format!(
+                    "{}: {}",
+                    i18n::format(locale, "stats-custom-rule", &[("host", region.as_str().into())]),
+                    links(*count)
+                ),
+                None => format!("🌍 **{} {}**: {}", provider, region.to_uppercase(), links(*count)),
+            })
             .collect::<Vec<_>>()
             .join("\n")
     };
 
     let embed = CreateEmbed::new()
-        .title("📊 Affilify Statistics")
-        .description("Link generation statistics for this server")
-        .field("🌐 Global Total", format!("{} links", global_count), true)
-        .field("🏠 This Server", format!("{} links", guild_count), true)
-        .field("📈 Top Regions", regions_text, false)
+        .title(i18n::text(locale, "stats-title"))
+        .description(i18n::text(locale, "stats-description"))
+        .field(i18n::text(locale, "stats-global"), totals(global_count, global_clicks), true)
+        .field(i18n::text(locale, "stats-server"), totals(guild_count, guild_clicks), true)
+        .field(i18n::text(locale, "stats-top-regions"), regions_text, false)
         .colour(Colour::from_rgb(52, 152, 219)) // Nice blue color
-        .footer(serenity::all::CreateEmbedFooter::new("Keep sharing those affiliate links! 💰"));
+        .footer(serenity::all::CreateEmbedFooter::new(i18n::text(locale, "stats-footer")));
 
     let response = CreateInteractionResponse::Message(
         CreateInteractionResponseMessage::new()
@@ -0,0 +1,158 @@
+// src/commands/watch.rs
+// Handles the `/watch` slash command: price watches on Amazon products with drop alerts.
+
+use serenity::all::{
+    Command, CommandInteraction, CommandOptionType, EditInteractionResponse,
+    InstallationContext, InteractionContext,
+    ResolvedOption, ResolvedValue,
+};
+use serenity::http::Http;
+use serenity::prelude::*;
+use url::Url;
+use super::super::providers::{amazon::Amazon, AffiliateProvider};
+use super::super::{i18n, marketplace, prices::PriceSource, resolver, utils, watch};
+
+/// Register the `/watch` slash command with its `add`, `list` and `remove` subcommands.
+pub async fn register_commands(http: &Http) {
+    let command = i18n::command("watch", "cmd-watch")
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "add", "cmd-watch-add")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::String, "link", "cmd-watch-add-link")
+                        .required(true)
+                )
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Number, "target_price", "cmd-watch-add-target-price")
+                        .min_number_value(0.01)
+                )
+        )
+        .add_option(i18n::option(CommandOptionType::SubCommand, "list", "cmd-watch-list"))
+        .add_option(
+            i18n::option(CommandOptionType::SubCommand, "remove", "cmd-watch-remove")
+                .add_sub_option(
+                    i18n::option(CommandOptionType::Integer, "id", "cmd-watch-remove-id")
+                        .required(true)
+                )
+        )
+        .dm_permission(true)
+        .integration_types(vec![
+            InstallationContext::Guild,
+            InstallationContext::User,
+        ])
+        .contexts(vec![
+            InteractionContext::Guild,
+            InteractionContext::BotDm,
+            InteractionContext::PrivateChannel,
+        ]);
+
+    let _ = Command::create_global_command(http, command).await;
+}
+
+/// Handler for the `/watch` command. Answers are ephemeral; looking up prices may take a moment, so the answer is deferred.
+pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
+    if cmd.defer_ephemeral(&ctx.http).await.is_err() {
+        return;
+    }
+
+    let user_id = cmd.user.id.get().to_string();
+    let guild_id = cmd.guild_id.map(|id| id.get().to_string());
+    let locale = i18n::resolve(guild_id.as_deref(), &cmd.locale);
+    let options = cmd.data.options();
+    let Some(ResolvedOption { name, value: ResolvedValue::SubCommand(sub_options), .. }) = options.first() else {
+        return;
+    };
+    let content = match *name {
+        "add" => run_add(cmd, &user_id, locale, sub_options).await,
+        "list" => run_list(&user_id, locale),
+        "remove" => {
+            let id = sub_options.iter().find_map(|opt| match opt.value {
+                ResolvedValue::Integer(id) if opt.name == "id" => Some(id),
+                _ => None,
+            });
+            match id.map(|id| watch::remove(&user_id, id)) {
+                Some(Ok(true)) => i18n::text(locale, "watch-removed"),
+                Some(Ok(false)) | None => i18n::text(locale, "watch-not-found"),
+                Some(Err(e)) => i18n::format(locale, "watch-remove-error", &[("error", format!("{:?}", e).into())]),
+            }
+        },
+        _ => return,
+    };
+    let _ = cmd.edit_response(&ctx.http, EditInteractionResponse::new().content(content)).await;
+}
+
+/// `/watch add` - resolves the link, checks the current price and stores the watch.
+async fn run_add(cmd: &CommandInteraction, user_id: &str, locale: &str, options: &[ResolvedOption<'_>]) -> String {
+    let link = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::String(link) if opt.name == "link" => Some(link.trim()),
+        _ => None,
+    }).unwrap_or("");
+    let target = options.iter().find_map(|opt| match opt.value {
+        ResolvedValue::Number(price) if opt.name == "target_price" => Some(price),
+        _ => None,
+    });
+
+    if watch::for_user(user_id).len() >= watch::MAX_WATCHES {
+        return i18n::format(locale, "watch-limit", &[("max", watch::MAX_WATCHES.into())]);
+    }
+
+    let url = if link.contains("://") { link.to_string() } else { format!("https://{}", link) };
+    let resolved = if utils::is_short_link(&url) {
+        match resolver::resolve_url(&url).await {
+            Ok(resolved) => resolved,
+            Err(_) => return i18n::text(locale, "watch-resolve-failed"),
+        }
+    } else {
+        url
+    };
+
+    let guild_id = cmd.guild_id.map(|id| id.get().to_string());
+    let product = Url::parse(&resolved).ok()
+        .and_then(|url| Amazon.parse(&url))
+        .and_then(|parsed| Some((parsed.region, parsed.item_id?)));
+    let Some((region, asin)) = product else {
+        return i18n::text(locale, "watch-not-a-product");
+    };
+
+    let current = PriceSource::from_config().current_price(guild_id.as_deref(), &region, &asin).await;
+    let target_price = match (target, &current) {
+        (Some(target), _) => target,
+        (None, Some(current)) => current.amount,
+        (None, None) => return i18n::text(locale, "watch-price-unknown"),
+    };
+
+    let domain = marketplace::find(&region).map(|m| m.domain()).unwrap_or_else(|| format!("amazon.{}", region));
+    match watch::add(user_id, guild_id.as_deref(), &region, &asin, target_price, &cmd.locale) {
+        Ok(id) => {
+            let mut args = vec![
+                ("asin", asin.as_str().into()),
+                ("domain", domain.into()),
+                ("id", id.into()),
+                ("target", format!("{:.2}", target_price).into()),
+            ];
+            match current {
+                Some(price) => {
+                    args.push(("price", price.display.into()));
+                    i18n::format(locale, "watch-added-with-price", &args)
+                },
+                None => i18n::format(locale, "watch-added", &args),
+            }
+        },
+        Err(e) => i18n::format(locale, "watch-save-error", &[("error", format!("{:?}", e).into())]),
+    }
+}
+
+/// `/watch list` - shows the user's watches with their IDs.
+fn run_list(user_id: &str, locale: &str) -> String {
+    let watches = watch::for_user(user_id);
+    if watches.is_empty() {
+        return i18n::text(locale, "watch-list-empty");
+    }
+    let lines: Vec<String> = watches.iter()
+        .map(|w| i18n::format(locale, "watch-list-entry", &[
+            ("id", w.id.into()),
+            ("url", w.product_url().into()),
+            ("target", format!("{:.2}", w.target_price).into()),
+        ]))
+        .collect();
+    format!("{}\n{}", i18n::text(locale, "watch-list-title"), lines.join("\n"))
+}
@@ -1,6 +1,8 @@
 // src/config.rs
 use dotenvy::dotenv;
 use std::env;
+use std::time::Duration;
+use super::marketplace::Marketplace;
 
 pub fn init() -> Result<(), dotenvy::Error> {
     dotenv().map(|_| ())
@@ -16,11 +18,94 @@ pub fn database_url() -> String {
     raw.strip_prefix("sqlite://").unwrap_or(&raw).to_string()
 }
 
-pub fn default_tracking_tag(region: &str) -> String {
-    let key = format!("DEFAULT_TRACKING_TAG_{}", region.to_uppercase().replace(".", "_"));
-    env::var(&key).unwrap_or_else(|_| String::new())
+pub fn default_tracking_tag(marketplace: &Marketplace) -> String {
+    env::var(marketplace.env_key()).unwrap_or_else(|_| String::new())
+}
+
+/// Developer fallback eBay Partner Network campaign ID (DEFAULT_TRACKING_TAG_EBAY)
+pub fn default_ebay_campaign_id() -> String {
+    env::var("DEFAULT_TRACKING_TAG_EBAY").unwrap_or_else(|_| String::new())
 }
 
 pub fn default_signature() -> String {
     env::var("DEFAULT_SIGNATURE").unwrap_or_else(|_| "🤖 Powered by Affilify Bot".to_string())
-}
\ No newline at end of file
+}
+
+/// How long a resolved short link is cached (RESOLVE_CACHE_TTL_SECS, default 7 days)
+pub fn resolve_cache_ttl() -> Duration {
+    env_secs("RESOLVE_CACHE_TTL_SECS", 7 * 24 * 60 * 60)
+}
+
+/// How long a failed short-link resolution is cached (RESOLVE_NEGATIVE_TTL_SECS, default 10 minutes)
+pub fn resolve_negative_ttl() -> Duration {
+    env_secs("RESOLVE_NEGATIVE_TTL_SECS", 10 * 60)
+}
+
+/// Operator credentials for the Product Advertising API (PAAPI_ACCESS_KEY, PAAPI_SECRET_KEY), used for guilds without their own
+pub fn paapi_credentials() -> Option<(String, String)> {
+    let access_key = env::var("PAAPI_ACCESS_KEY").ok().filter(|key| !key.trim().is_empty())?;
+    let secret_key = env::var("PAAPI_SECRET_KEY").ok().filter(|key| !key.trim().is_empty())?;
+    Some((access_key.trim().to_string(), secret_key.trim().to_string()))
+}
+
+/// Base URL replacing `https://webservices.amazon.{tld}`, e.g. a local mock (PAAPI_ENDPOINT)
+pub fn paapi_endpoint() -> Option<String> {
+    env::var("PAAPI_ENDPOINT").ok()
+        .map(|endpoint| endpoint.trim().trim_end_matches('/').to_string())
+        .filter(|endpoint| !endpoint.is_empty())
+}
+
+/// How long product details from the Product Advertising API are cached (PRODUCT_CACHE_TTL_SECS, default 1 hour)
+pub fn product_cache_ttl() -> Duration {
+    env_secs("PRODUCT_CACHE_TTL_SECS", 60 * 60)
+}
+
+/// Price source for price watches: "paapi" (default) or "stub" (PRICE_SOURCE)
+pub fn price_source() -> String {
+    env::var("PRICE_SOURCE").map(|source| source.trim().to_lowercase()).unwrap_or_default()
+}
+
+/// JSON file with prices for the stub price source (PRICE_STUB_FILE, default ./data/prices.json)
+pub fn price_stub_file() -> String {
+    env::var("PRICE_STUB_FILE").unwrap_or_else(|_| "./data/prices.json".to_string())
+}
+
+/// How often price watches are checked (WATCH_INTERVAL_SECS, default 1 hour)
+pub fn watch_interval() -> Duration {
+    env_secs("WATCH_INTERVAL_SECS", 60 * 60)
+}
+
+/// Public base URL of the click-tracking redirect server, e.g. `https://go.example` (CLICK_BASE_URL);
+/// without it links are posted directly and the server doesn't start
+pub fn click_base_url() -> Option<String> {
+    env::var("CLICK_BASE_URL").ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Address the click-tracking redirect server listens on (CLICK_LISTEN_ADDR, default 0.0.0.0:8080)
+pub fn click_listen_addr() -> String {
+    env::var("CLICK_LISTEN_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+}
+
+/// A rate limit from an env var like `RATE_LIMIT_USER=10/60` (10 links per 60 seconds);
+/// `off` disables the limit
+pub fn rate_limit(key: &str, default: (u32, u64)) -> Option<(u32, Duration)> {
+    let raw = env::var(key).unwrap_or_default();
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("off") {
+        return None;
+    }
+    let (count, secs) = raw.split_once('/')
+        .and_then(|(count, secs)| Some((count.trim().parse().ok()?, secs.trim().parse().ok()?)))
+        .filter(|(count, secs)| *count > 0 && *secs > 0)
+        .unwrap_or(default);
+    Some((count, Duration::from_secs(secs)))
+}
+
+fn env_secs(key: &str, default: u64) -> Duration {
+    let secs = env::var(key).ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default);
+    Duration::from_secs(secs)
+}
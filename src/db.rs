@@ -1,5 +1,5 @@
 // src/db.rs
-use rusqlite::Connection;
+use rusqlite::{params, Connection};
 use std::path::Path;
 
 pub fn init() -> rusqlite::Result<()> {
@@ -15,35 +15,290 @@ pub fn init() -> rusqlite::Result<()> {
         })?;
     }
     
-    let init = !Path::new(&db_path).exists();
     let conn = Connection::open(&db_path)?;
-    if init {
+    // Every statement is idempotent so tables added in later versions are created on existing databases
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS guild_affiliates (
+            guild_id TEXT NOT NULL,
+            provider TEXT NOT NULL DEFAULT 'amazon',
+            region TEXT NOT NULL,
+            tracking_tag TEXT NOT NULL,
+            PRIMARY KEY (guild_id, provider, region)
+        );
+        CREATE TABLE IF NOT EXISTS guild_settings (
+            guild_id TEXT PRIMARY KEY,
+            footer_text TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS link_stats (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            guild_id TEXT NOT NULL,
+            region TEXT NOT NULL,
+            timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE IF NOT EXISTS channel_settings (
+            channel_id TEXT PRIMARY KEY,
+            guild_id TEXT NOT NULL,
+            link_mode TEXT
+        );
+        CREATE TABLE IF NOT EXISTS guild_channel_filters (
+            guild_id TEXT NOT NULL,
+            channel_id TEXT NOT NULL,
+            mode TEXT NOT NULL,
+            PRIMARY KEY (guild_id, channel_id)
+        );
+        CREATE TABLE IF NOT EXISTS guild_role_filters (
+            guild_id TEXT NOT NULL,
+            role_id TEXT NOT NULL,
+            mode TEXT NOT NULL,
+            PRIMARY KEY (guild_id, role_id)
+        );
+        CREATE TABLE IF NOT EXISTS guild_kept_params (
+            guild_id TEXT NOT NULL,
+            param TEXT NOT NULL,
+            PRIMARY KEY (guild_id, param)
+        );
+        CREATE TABLE IF NOT EXISTS guild_rewrite_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            guild_id TEXT NOT NULL,
+            host_pattern TEXT NOT NULL,
+            strip_params TEXT NOT NULL DEFAULT '',
+            add_params TEXT NOT NULL DEFAULT '',
+            UNIQUE (guild_id, host_pattern)
+        );
+        CREATE TABLE IF NOT EXISTS resolved_links (
+            short_url TEXT PRIMARY KEY,
+            resolved_url TEXT,
+            error TEXT,
+            resolved_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS bot_replies (
+            message_id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL,
+            reply_message_id TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            links TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS pending_deletions (
+            message_id TEXT PRIMARY KEY,
+            channel_id TEXT NOT NULL,
+            delete_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS recent_products (
+            channel_id TEXT NOT NULL,
+            product_key TEXT NOT NULL,
+            reply_message_id TEXT NOT NULL,
+            answered_at INTEGER NOT NULL,
+            PRIMARY KEY (channel_id, product_key)
+        );
+        CREATE TABLE IF NOT EXISTS guild_paapi_credentials (
+            guild_id TEXT PRIMARY KEY,
+            access_key TEXT NOT NULL,
+            secret_key TEXT NOT NULL,
+            partner_tag TEXT
+        );
+        CREATE TABLE IF NOT EXISTS product_cache (
+            region TEXT NOT NULL,
+            asin TEXT NOT NULL,
+            title TEXT,
+            image_url TEXT,
+            price TEXT,
+            prime INTEGER NOT NULL DEFAULT 0,
+            rating REAL,
+            review_count INTEGER,
+            amount REAL,
+            fetched_at INTEGER NOT NULL,
+            PRIMARY KEY (region, asin)
+        );
+        CREATE TABLE IF NOT EXISTS price_watches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id TEXT NOT NULL,
+            guild_id TEXT,
+            region TEXT NOT NULL,
+            asin TEXT NOT NULL,
+            target_price REAL NOT NULL,
+            locale TEXT,
+            created_at INTEGER NOT NULL,
+            UNIQUE (user_id, region, asin)
+        );
+        CREATE TABLE IF NOT EXISTS short_links (
+            id TEXT PRIMARY KEY,
+            target_url TEXT NOT NULL,
+            guild_id TEXT NOT NULL,
+            message_id TEXT,
+            provider TEXT NOT NULL,
+            region TEXT NOT NULL,
+            item_id TEXT,
+            created_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS clicks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            short_link_id TEXT NOT NULL,
+            guild_id TEXT NOT NULL,
+            message_id TEXT,
+            region TEXT NOT NULL,
+            item_id TEXT,
+            user_agent_hash TEXT NOT NULL,
+            clicked_at INTEGER NOT NULL
+        );"
+    )?;
+
+    // Columns added to existing tables after the first release
+    add_column(&conn, "guild_settings", "foreign_tag_policy", "TEXT")?;
+    add_column(&conn, "guild_settings", "mod_channel_id", "TEXT")?;
+    add_column(&conn, "guild_settings", "link_mode", "TEXT")?;
+    add_column(&conn, "guild_settings", "hint_text", "TEXT")?;
+    add_column(&conn, "guild_settings", "hint_lifetime_secs", "TEXT")?;
+    add_column(&conn, "guild_settings", "dedup_mode", "TEXT")?;
+    add_column(&conn, "guild_settings", "dedup_window_mins", "TEXT")?;
+    add_column(&conn, "guild_settings", "deals_channel_id", "TEXT")?;
+    add_column(&conn, "guild_settings", "also_offer", "TEXT")?;
+    add_column(&conn, "guild_settings", "locale", "TEXT")?;
+    add_column(&conn, "link_stats", "provider", "TEXT NOT NULL DEFAULT 'amazon'")?;
+
+    // The provider is part of the guild_affiliates primary key, so older tables are rebuilt
+    if !has_column(&conn, "guild_affiliates", "provider")? {
         conn.execute_batch(
-            "CREATE TABLE guild_affiliates (
+            "BEGIN;
+            CREATE TABLE guild_affiliates_new (
                 guild_id TEXT NOT NULL,
+                provider TEXT NOT NULL DEFAULT 'amazon',
                 region TEXT NOT NULL,
                 tracking_tag TEXT NOT NULL,
-                PRIMARY KEY (guild_id, region)
+                PRIMARY KEY (guild_id, provider, region)
             );
-            CREATE TABLE guild_settings (
-                guild_id TEXT PRIMARY KEY,
-                footer_text TEXT NOT NULL
-            );
-            CREATE TABLE link_stats (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                guild_id TEXT NOT NULL,
-                region TEXT NOT NULL,
-                timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
-            );"
+            INSERT INTO guild_affiliates_new (guild_id, provider, region, tracking_tag)
+                SELECT guild_id, 'amazon', region, tracking_tag FROM guild_affiliates;
+            DROP TABLE guild_affiliates;
+            ALTER TABLE guild_affiliates_new RENAME TO guild_affiliates;
+            COMMIT;"
         )?;
     }
+
+    // Before the marketplace registry, Brazil was configured as "br" (its links have the region "com.br")
+    // and China could be configured although amazon.cn has no Associates program
+    conn.execute_batch(
+        "UPDATE OR IGNORE guild_affiliates SET region = 'com.br' WHERE provider = 'amazon' AND region = 'br';
+        DELETE FROM guild_affiliates WHERE provider = 'amazon' AND region IN ('br', 'cn');"
+    )?;
+
+    // Drop short-link cache entries that can no longer be used
+    conn.execute(
+        "DELETE FROM resolved_links WHERE resolved_at < ?",
+        params![chrono::Utc::now().timestamp() - super::config::resolve_cache_ttl().as_secs() as i64],
+    )?;
+    // Product details that are too old to be shown
+    conn.execute(
+        "DELETE FROM product_cache WHERE fetched_at < ?",
+        params![chrono::Utc::now().timestamp() - super::config::product_cache_ttl().as_secs() as i64],
+    )?;
+    // Answered products outside of the longest dedup window
+    conn.execute(
+        "DELETE FROM recent_products WHERE answered_at < ?",
+        params![chrono::Utc::now().timestamp() - (*super::dedup::WINDOW_RANGE_MINS.end() * 60) as i64],
+    )?;
+    // Forget replies to messages that are unlikely to be edited anymore
+    conn.execute(
+        "DELETE FROM bot_replies WHERE created_at < ?",
+        params![chrono::Utc::now().timestamp() - super::replies::RETENTION_SECS],
+    )?;
+    // Clicks and short links past their retention
+    conn.execute(
+        "DELETE FROM clicks WHERE clicked_at < ?",
+        params![chrono::Utc::now().timestamp() - super::clicks::CLICK_RETENTION_DAYS * 24 * 60 * 60],
+    )?;
+    conn.execute(
+        "DELETE FROM short_links WHERE created_at < ?",
+        params![chrono::Utc::now().timestamp() - super::clicks::LINK_RETENTION_DAYS * 24 * 60 * 60],
+    )?;
+    // Deletions that kept failing long after their time
+    conn.execute(
+        "DELETE FROM pending_deletions WHERE delete_at < ?",
+        params![chrono::Utc::now().timestamp() - super::cleanup::GIVE_UP_SECS],
+    )?;
+    Ok(())
+}
+
+/// Check whether a table has a column
+fn has_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    Ok(conn.query_row(
+        &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?", table),
+        params![column],
+        |r| r.get::<_, i64>(0),
+    )? > 0)
+}
+
+/// Add a column to an existing table unless it is already present
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    if !has_column(conn, table, column)? {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))?;
+    }
     Ok(())
 }
 
+/// Read a single `guild_settings` column. `column` must be a column name from code, never user input.
+pub fn guild_setting(guild_id: &str, column: &str) -> Option<String> {
+    with_connection(|conn| {
+        Ok(conn.query_row(
+            &format!("SELECT {} FROM guild_settings WHERE guild_id = ?", column),
+            params![guild_id],
+            |r| r.get::<_, Option<String>>(0),
+        ).ok().flatten())
+    })
+    .unwrap_or(None)
+    .filter(|value| !value.is_empty())
+}
+
+/// Write a single `guild_settings` column, creating the row if needed (an empty footer means "not set").
+/// `column` must be a column name from code, never user input.
+pub fn set_guild_setting(guild_id: &str, column: &str, value: Option<&str>) -> rusqlite::Result<()> {
+    with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO guild_settings (guild_id, footer_text) VALUES (?, '') ON CONFLICT(guild_id) DO NOTHING",
+            params![guild_id],
+        )?;
+        conn.execute(
+            &format!("UPDATE guild_settings SET {} = ? WHERE guild_id = ?", column),
+            params![value, guild_id],
+        )?;
+        Ok(())
+    })
+}
+
+/// Read a single `channel_settings` column (per-channel overrides of guild settings).
+/// `column` must be a column name from code, never user input.
+pub fn channel_setting(channel_id: &str, column: &str) -> Option<String> {
+    with_connection(|conn| {
+        Ok(conn.query_row(
+            &format!("SELECT {} FROM channel_settings WHERE channel_id = ?", column),
+            params![channel_id],
+            |r| r.get::<_, Option<String>>(0),
+        ).ok().flatten())
+    })
+    .unwrap_or(None)
+    .filter(|value| !value.is_empty())
+}
+
+/// Write a single `channel_settings` column, creating the row if needed; `None` falls back to the guild setting.
+/// `column` must be a column name from code, never user input.
+pub fn set_channel_setting(guild_id: &str, channel_id: &str, column: &str, value: Option<&str>) -> rusqlite::Result<()> {
+    with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO channel_settings (channel_id, guild_id) VALUES (?, ?) ON CONFLICT(channel_id) DO NOTHING",
+            params![channel_id, guild_id],
+        )?;
+        conn.execute(
+            &format!("UPDATE channel_settings SET {} = ? WHERE channel_id = ?", column),
+            params![value, channel_id],
+        )?;
+        Ok(())
+    })
+}
+
 pub fn with_connection<F, T>(f: F) -> rusqlite::Result<T>
 where
     F: FnOnce(&Connection) -> rusqlite::Result<T>,
 {
-    let conn = Connection::open(&super::config::database_url())?;
+    let conn = Connection::open(super::config::database_url())?;
     f(&conn)
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+// src/dedup.rs
+// Repeated products: when the same product is posted again in a channel shortly after the bot
+// answered it, the bot reacts, links to its earlier answer or stays quiet instead of answering again.
+
+use std::time::Duration;
+use rusqlite::params;
+use serenity::all::{ChannelId, MessageId};
+use super::{db, utils::ProcessedLink};
+
+/// Window used unless the guild configured one
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Allowed range for a guild's window, in minutes
+pub const WINDOW_RANGE_MINS: std::ops::RangeInclusive<u64> = 1..=24 * 60;
+
+/// What the bot does when a product is posted again within the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupMode {
+    /// Answer every post (default)
+    Off,
+    /// React to the repeat with 🔁
+    React,
+    /// Reply with a link to the earlier answer
+    JumpLink,
+    /// Don't respond to the repeat at all
+    Silent,
+}
+
+impl DedupMode {
+    pub const ALL: [DedupMode; 4] = [Self::Off, Self::React, Self::JumpLink, Self::Silent];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::React => "react",
+            Self::JumpLink => "jump-link",
+            Self::Silent => "silent",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == value)
+    }
+
+    pub fn for_guild(guild_id: &str) -> Self {
+        db::guild_setting(guild_id, "dedup_mode")
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or(Self::Off)
+    }
+}
+
+/// How long a product counts as answered in a channel of the guild
+pub fn window(guild_id: &str) -> Duration {
+    db::guild_setting(guild_id, "dedup_window_mins")
+        .and_then(|mins| mins.parse::<u64>().ok())
+        .filter(|mins| WINDOW_RANGE_MINS.contains(mins))
+        .map(|mins| Duration::from_secs(mins * 60))
+        .unwrap_or(DEFAULT_WINDOW)
+}
+
+/// Identifies a product across posts, e.g. "amazon:de:B08N5WRWNW"; links to searches, stores, ... have none
+fn product_key(link: &ProcessedLink) -> Option<String> {
+    let item_id = link.item_id.as_ref()?;
+    Some(format!("{}:{}:{}", link.network, link.region, item_id))
+}
+
+/// The bot's answer to the product in this channel within the window, if any
+pub fn earlier_answer(channel_id: ChannelId, link: &ProcessedLink, window: Duration) -> Option<MessageId> {
+    let key = product_key(link)?;
+    let since = chrono::Utc::now().timestamp() - window.as_secs() as i64;
+    db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT reply_message_id FROM recent_products WHERE channel_id = ? AND product_key = ? AND answered_at >= ?",
+            params![channel_id.get().to_string(), key, since],
+            |r| r.get::<_, String>(0),
+        )
+    })
+    .ok()
+    .and_then(|id| id.parse::<u64>().ok())
+    .filter(|id| *id != 0)
+    .map(MessageId::new)
+}
+
+/// Remember that a reply answered these products in the channel
+pub fn remember(channel_id: ChannelId, links: &[ProcessedLink], reply_id: MessageId) {
+    let now = chrono::Utc::now().timestamp();
+    let _ = db::with_connection(|conn| {
+        for key in links.iter().filter_map(product_key) {
+            conn.execute(
+                "INSERT OR REPLACE INTO recent_products (channel_id, product_key, reply_message_id, answered_at) VALUES (?, ?, ?, ?)",
+                params![channel_id.get().to_string(), key, reply_id.get().to_string(), now],
+            )?;
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,195 @@
+// src/i18n.rs
+// Message catalog for everything the bot says. Catalogs are Fluent files in `locales/`, compiled into
+// the binary; a message missing from a catalog falls back to English. Languages without a full
+// catalog may still have a translated hint for link-only messages in `locales/hints/`.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption};
+use unic_langid::LanguageIdentifier;
+use super::db;
+
+pub use fluent_bundle::FluentValue;
+
+/// Language used when nothing else is known, and for messages a catalog doesn't translate
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// A catalog and the Discord locales its command localizations are registered for
+struct Catalog {
+    language: &'static str,
+    discord_locales: &'static [&'static str],
+    source: &'static str,
+}
+
+const CATALOGS: &[Catalog] = &[
+    Catalog { language: "en", discord_locales: &[], source: include_str!("../locales/en.ftl") },
+    Catalog { language: "de", discord_locales: &["de"], source: include_str!("../locales/de.ftl") },
+];
+
+/// Languages that only translate `hint-default`, as (language, source). They are no catalogs: guilds can't
+/// choose them and nothing but the hint for link-only messages is shown in them.
+const HINT_FALLBACKS: &[(&str, &str)] = &[
+    ("fr", include_str!("../locales/hints/fr.ftl")),
+    ("es", include_str!("../locales/hints/es.ftl")),
+    ("it", include_str!("../locales/hints/it.ftl")),
+];
+
+static BUNDLES: LazyLock<HashMap<&'static str, FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    CATALOGS.iter()
+        .map(|catalog| (catalog.language, catalog.source))
+        .chain(HINT_FALLBACKS.iter().copied())
+        .map(|(language, source)| (language, bundle(language, source)))
+        .collect()
+});
+
+fn bundle(language: &str, source: &str) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language.parse().expect("valid catalog language");
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Discord shows the Unicode isolation marks Fluent puts around arguments
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid catalog {}: {:?}", language, errors));
+    bundle.add_resource(resource)
+        .unwrap_or_else(|errors| panic!("invalid catalog {}: {:?}", language, errors));
+    bundle
+}
+
+/// Catalog language for a Discord locale ("de", "en-US", "es-419"), if there is a catalog for it
+pub fn supported(locale: &str) -> Option<&'static str> {
+    let language = locale.split('-').next()?.to_lowercase();
+    CATALOGS.iter().map(|catalog| catalog.language).find(|l| *l == language)
+}
+
+/// Languages a guild can choose with `/configure language`: catalogs that translate every message, with their own name
+pub fn languages() -> Vec<(&'static str, String)> {
+    CATALOGS.iter()
+        .filter(|catalog| BUNDLES[catalog.language].has_message("language-name"))
+        .map(|catalog| (catalog.language, text(catalog.language, "language-name")))
+        .collect()
+}
+
+/// Language of the bot's output: the guild's configured language, else `fallback`
+/// (the interaction's or the guild's Discord locale), else English
+pub fn resolve(guild_id: Option<&str>, fallback: &str) -> &'static str {
+    guild_id
+        .and_then(|guild_id| db::guild_setting(guild_id, "locale"))
+        .and_then(|locale| supported(&locale))
+        .or_else(|| supported(fallback))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Language of the hint for link-only messages: as `resolve`, but a Discord locale without a catalog
+/// still gets its translated hint from `HINT_FALLBACKS`
+pub fn resolve_hint(guild_id: Option<&str>, fallback: &str) -> &'static str {
+    let configured = guild_id
+        .and_then(|guild_id| db::guild_setting(guild_id, "locale"))
+        .and_then(|locale| supported(&locale));
+    let language = fallback.split('-').next().unwrap_or_default().to_lowercase();
+    configured
+        .or_else(|| supported(fallback))
+        .or_else(|| HINT_FALLBACKS.iter().map(|(l, _)| *l).find(|l| *l == language))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// A message without arguments
+pub fn text(locale: &str, id: &str) -> String {
+    format(locale, id, &[])
+}
+
+/// A message with arguments; unknown messages come out as their id
+pub fn format(locale: &str, id: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    [locale, DEFAULT_LOCALE].iter()
+        .filter_map(|language| BUNDLES.get(language))
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned())
+        })
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Translations of a command, option or choice per Discord locale: the message `id`
+/// as description and its `.name` attribute as name, where a catalog has them
+fn localizations(id: &str) -> Vec<(&'static str, Option<String>, Option<String>)> {
+    let mut localizations = Vec::new();
+    for catalog in CATALOGS {
+        let bundle = &BUNDLES[catalog.language];
+        let Some(message) = bundle.get_message(id) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let description = message.value()
+            .map(|pattern| bundle.format_pattern(pattern, None, &mut errors).into_owned());
+        let name = message.get_attribute("name")
+            .map(|attribute| bundle.format_pattern(attribute.value(), None, &mut errors).into_owned());
+        for locale in catalog.discord_locales {
+            localizations.push((*locale, name.clone(), description.clone()));
+        }
+    }
+    localizations
+}
+
+/// A slash command described by the message `id`, with Discord localizations from every catalog
+pub fn command(name: &str, id: &str) -> CreateCommand {
+    let mut command = CreateCommand::new(name).description(text(DEFAULT_LOCALE, id));
+    for (locale, name, description) in localizations(id) {
+        if let Some(name) = name {
+            command = command.name_localized(locale, name);
+        }
+        if let Some(description) = description {
+            command = command.description_localized(locale, description);
+        }
+    }
+    command
+}
+
+/// A command option described by the message `id`, with Discord localizations from every catalog
+pub fn option(kind: CommandOptionType, name: &str, id: &str) -> CreateCommandOption {
+    let mut option = CreateCommandOption::new(kind, name, text(DEFAULT_LOCALE, id));
+    for (locale, name, description) in localizations(id) {
+        if let Some(name) = name {
+            option = option.name_localized(locale, name);
+        }
+        if let Some(description) = description {
+            option = option.description_localized(locale, description);
+        }
+    }
+    option
+}
+
+/// Add a string choice labeled by the message `id`, with Discord localizations from every catalog
+pub fn add_choice(option: CreateCommandOption, id: &str, value: &str) -> CreateCommandOption {
+    let labels = localizations(id).into_iter()
+        .filter_map(|(locale, _, label)| Some((locale, label?)));
+    option.add_string_choice_localized(text(DEFAULT_LOCALE, id), value, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hint_fallbacks_are_no_catalogs() {
+        for (language, _) in HINT_FALLBACKS {
+            assert_eq!(supported(language), None);
+            assert!(!languages().iter().any(|(l, _)| l == language));
+        }
+    }
+
+    #[test]
+    fn hint_fallbacks_translate_only_the_hint() {
+        assert_eq!(resolve_hint(None, "es-419"), "es");
+        assert_eq!(resolve_hint(None, "de"), "de");
+        assert_eq!(resolve_hint(None, "ja"), DEFAULT_LOCALE);
+        assert_eq!(resolve(None, "fr"), DEFAULT_LOCALE);
+
+        let hint = format("it", "hint-default", &[("sender", "@a".into()), ("command", "/amazon".into())]);
+        assert_eq!(hint, "@a, usa /amazon per pulire e taggare il tuo link.");
+        assert_eq!(text("it", "language-name"), text(DEFAULT_LOCALE, "language-name"));
+    }
+}
@@ -2,17 +2,35 @@
 // Entry point for Affilify Discord bot in Rust (MIT License)
 use serenity::{
     async_trait,
-    all::{Ready, Interaction, Message, CreateMessage, Mentionable, CreateButton, CreateActionRow},
+    all::{
+        Ready, Interaction, Message, CreateMessage, EditMessage,
+        Mentionable, CreateButton, CreateActionRow, CreateEmbed, Channel, ChannelId, MessageUpdateEvent,
+    },
     prelude::*,
 };
 
+mod cleanup;
+mod clicks;
 mod config;
 mod db;
+mod dedup;
+mod i18n;
+mod marketplace;
+mod paapi;
+mod prices;
+mod providers;
+mod ratelimit;
+mod replies;
+mod resolver;
+mod rules;
 mod utils;
+mod watch;
+mod webhook;
 mod commands {
     pub mod amazon;
     pub mod configure;
     pub mod stats;
+    pub mod watch;
 }
 
 struct Handler;
@@ -23,10 +41,15 @@ impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         const VERSION: &str = env!("CARGO_PKG_VERSION");
         println!("🤖 {} v{} is connected and ready!", ready.user.name, VERSION);
+        // Finish hint deletions that were pending when the bot stopped
+        cleanup::resume(&ctx.http);
         // Register slash commands at startup
         commands::configure::register_commands(&ctx.http).await;
         commands::amazon::register_commands(&ctx.http).await;
         commands::stats::register_commands(&ctx.http).await;
+        commands::watch::register_commands(&ctx.http).await;
+        // Check price watches on a schedule
+        watch::start(&ctx.http);
     }
 
     /// Handle incoming interactions (slash commands, autocomplete, modals).
@@ -37,119 +60,515 @@ impl EventHandler for Handler {
                     "configure" => commands::configure::run(&ctx, cmd).await,
                     "amazon"    => commands::amazon::run(&ctx, cmd).await,
                     "stats"     => commands::stats::run(&ctx, cmd).await,
+                    "watch"     => commands::watch::run(&ctx, cmd).await,
                     _            => {}
                 }
             },
-            Interaction::Autocomplete(autocomplete) => {
-                match autocomplete.data.name.as_str() {
-                    "configure" => commands::configure::handle_autocomplete(&ctx, &interaction).await,
-                    _ => {}
-                }
+            Interaction::Autocomplete(autocomplete) if autocomplete.data.name == "configure" => {
+                commands::configure::handle_autocomplete(&ctx, &interaction).await;
             },
-            Interaction::Modal(modal) => {
-                if modal.data.custom_id.starts_with("config_modal_") {
-                    commands::configure::handle_modal(&ctx, &interaction).await;
-                }
+            Interaction::Modal(modal) if modal.data.custom_id.starts_with("config_modal_") => {
+                commands::configure::handle_modal(&ctx, &interaction).await;
+            },
+            Interaction::Modal(modal) if modal.data.custom_id == commands::configure::PRODUCT_API_MODAL => {
+                commands::configure::handle_product_api_modal(&ctx, &interaction).await;
             },
             _ => {}
         }
     }
 
-    /// Monitor all messages: smart handling of Amazon links based on message content.
+    /// Monitor all messages: smart handling of affiliate links based on message content.
     async fn message(&self, ctx: Context, msg: Message) {
-        // Ignore messages from bots
-        if msg.author.bot {
+        process_message(&ctx, &msg, None).await;
+    }
+
+    /// Run edited messages through the same pipeline; the bot's earlier reply is updated,
+    /// or deleted if the message no longer gets one.
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        // Link previews and flag changes (e.g. our own embed suppression) also arrive as updates
+        if event.guild_id.is_none() || event.content.is_none() || event.edited_timestamp.is_none() {
             return;
         }
 
-        // Skip deletion logic in DMs
-        if msg.guild_id.is_none() {
+        let mut msg = match new {
+            Some(msg) => msg,
+            None => match event.channel_id.message(&ctx, event.id).await {
+                Ok(msg) => msg,
+                Err(_) => return,
+            },
+        };
+        // Fetched messages come without guild and member; the event carries both
+        msg.guild_id = event.guild_id;
+        if let Some(Some(member)) = event.member {
+            msg.member = Some(member);
+        }
+
+        let previous = replies::find(msg.id);
+        if previous.as_ref().is_some_and(|previous| previous.content_hash == replies::content_hash(&msg.content)) {
             return;
         }
+        let replied = process_message(&ctx, &msg, previous.as_ref()).await;
+        if let (false, Some(previous)) = (replied, previous) {
+            let _ = msg.channel_id.delete_message(&ctx.http, previous.reply_id).await;
+            replies::forget(msg.id);
+        }
+    }
+}
 
-        let content = msg.content.trim();
-        
-        // Check if message contains Amazon links
-        if content.contains("amazon.") || content.contains("amzn.to") {
-            let amazon_urls = utils::extract_amazon_urls(content);
-            
-            if amazon_urls.is_empty() {
-                return;
-            }
+/// Handle the affiliate links of a new or edited message. `previous` is the bot's earlier reply
+/// to an edited message, which is updated instead of sending another one.
+/// Returns whether the message has a reply from the bot afterwards.
+async fn process_message(ctx: &Context, msg: &Message, previous: Option<&replies::Reply>) -> bool {
+    // Ignore messages from bots and webhooks (including our own reposts)
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return false;
+    }
+
+    // Skip deletion logic in DMs
+    if msg.guild_id.is_none() {
+        return false;
+    }
+
+    let content = msg.content.trim();
+    
+    // Check if message contains affiliate links of any supported network (including short-link hosts)
+    // or links covered by one of the guild's rewrite rules
+    let guild_id = msg.guild_id.map(|id| id.get().to_string());
+    let rules = guild_id.as_deref().map(rules::for_guild).unwrap_or_default();
+    let urls = utils::extract_affiliate_urls(content, &rules);
+    if urls.is_empty() {
+        return false;
+    }
+
+    let guild_id_str = guild_id.clone().unwrap_or_else(|| "DM".to_string());
+
+    // Skip channels excluded by the guild's allowlist/denylist; threads follow their parent
+    let chain: Vec<String> = channel_chain(ctx, msg.channel_id).await
+        .iter()
+        .map(|id| id.get().to_string())
+        .collect();
+    if !utils::is_channel_processed(&guild_id_str, &chain) {
+        return false;
+    }
+
+    // Skip exempt members (and members without a processed role); the roles come with the message
+    let roles: Vec<String> = msg.member.as_ref()
+        .map(|member| member.roles.iter().map(|role| role.get().to_string()).collect())
+        .unwrap_or_default();
+    if !utils::is_member_processed(&guild_id_str, &roles) {
+        return false;
+    }
+
+    // Threads use their parent channel's mode unless they have their own
+    let mode = utils::LinkMode::for_channel(&guild_id_str, &chain);
+    if mode == utils::LinkMode::Off {
+        return false;
+    }
+    let locale = message_locale(ctx, msg);
 
-            // Determine if this is a link-only message or mixed content
-            if utils::is_amazon_link_only(content) {
-                // Link-only message: delete and show hint (current behavior)
+    // One token per link from the user's, channel's and guild's rate limits.
+    // Over the limit the bot stays quiet apart from a single cooldown message.
+    if let Err(limited) = ratelimit::acquire(msg.author.id, msg.channel_id, msg.guild_id, urls.len() as u32) {
+        if limited.notify {
+            send_temporary_notice(ctx, msg, format!(
+                "{} {}",
+                msg.author.id.mention(),
+                ratelimit::cooldown_message(&limited, locale)
+            )).await;
+        }
+        return false;
+    }
+
+    let policy = utils::ForeignTagPolicy::for_guild(guild_id.as_deref());
+    let mut link_only = utils::is_affiliate_link_only(content, &rules);
+
+    // Clean and tag all affiliate URLs in the message concurrently, keeping their order.
+    // Link-only messages under the default mode and policy are deleted anyway, so skip resolving them.
+    let mut links = Vec::new();
+    let deleted_anyway = link_only
+        && mode == utils::LinkMode::DeleteAndHint
+        && policy == utils::ForeignTagPolicy::Replace;
+    if !deleted_anyway {
+        links = utils::process_links(&urls, guild_id.clone(), &rules).await;
+    }
+
+    // Apply the guild's policy to links that already carry someone else's affiliate tag
+    let foreign: Vec<&providers::Attribution> = links.iter()
+        .filter_map(|link| link.foreign_attribution.as_ref())
+        .collect();
+    if !foreign.is_empty() {
+        match policy {
+            utils::ForeignTagPolicy::Replace => {},
+            utils::ForeignTagPolicy::Keep => {
+                // Leave tagged links (and the message) alone, only offer buttons for the rest
+                link_only = false;
+                links.retain(|link| link.foreign_attribution.is_none());
+            },
+            utils::ForeignTagPolicy::Reject => {
                 let _ = msg.delete(&ctx.http).await;
+                send_temporary_notice(ctx, msg, i18n::format(locale, "foreign-tag-rejected", &[
+                    ("sender", msg.author.id.mention().to_string().into()),
+                ])).await;
+                return false;
+            },
+            utils::ForeignTagPolicy::Flag => {
+                report_foreign_tags(ctx, msg, &foreign, locale).await;
+            },
+        }
+    }
 
-                let mention = msg.author.id.mention();
-                let message = CreateMessage::new()
-                    .content(format!(
-                        "{}, please use `/amazon <link>` to clean and tag your URL.",
-                        mention
-                    ));
-                    
-                if let Ok(sent) = msg.channel_id.send_message(&ctx.http, message).await {
-                    // Auto-delete the hint after 10 seconds
-                    let http = ctx.http.clone();
-                    tokio::spawn(async move {
-                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                        let _ = sent.delete(&http).await;
-                    });
-                }
+    // Products the bot answered in this channel a moment ago get no second answer; they still count as mentions
+    let dedup_mode = dedup::DedupMode::for_guild(&guild_id_str);
+    let answers_in_reply = match mode {
+        utils::LinkMode::DeleteAndHint => !link_only,
+        utils::LinkMode::WebhookRepost | utils::LinkMode::Off => false,
+        _ => true,
+    };
+    if answers_in_reply && dedup_mode != dedup::DedupMode::Off {
+        let window = dedup::window(&guild_id_str);
+        let mut repeats = Vec::new();
+        let mut earlier = None;
+        let mut fresh = Vec::new();
+        for link in links {
+            // After an edit, the bot's own reply to this message is no earlier answer
+            let answer = dedup::earlier_answer(msg.channel_id, &link, window)
+                .filter(|answer| previous.is_none_or(|previous| previous.reply_id != *answer));
+            match answer {
+                Some(answer) => {
+                    earlier.get_or_insert(answer);
+                    repeats.push(link);
+                },
+                None => fresh.push(link),
+            }
+        }
+        log_links(&repeats, previous, &guild_id_str);
+        links = fresh;
+
+        if let (true, Some(earlier)) = (links.is_empty(), earlier) {
+            return match dedup_mode {
+                dedup::DedupMode::React => {
+                    let _ = msg.react(&ctx.http, '🔁').await;
+                    false
+                },
+                dedup::DedupMode::JumpLink => {
+                    let content = i18n::format(locale, "dedup-already-shared", &[
+                        ("link", earlier.link(msg.channel_id, msg.guild_id).into()),
+                    ]);
+                    post_reply(ctx, msg, previous, content, Vec::new(), Vec::new(), true).await
+                },
+                dedup::DedupMode::Silent | dedup::DedupMode::Off => false,
+            };
+        }
+    }
+
+    let replied = match mode {
+        utils::LinkMode::DeleteAndHint if link_only => {
+            // Link-only message: delete and show hint
+            let _ = msg.delete(&ctx.http).await;
+            let hint = utils::guild_hint(&guild_id_str, &guild_discord_locale(ctx, msg), &msg.author.id.mention().to_string());
+            send_temporary_notice(ctx, msg, hint).await;
+            false
+        },
+        utils::LinkMode::DeleteAndHint | utils::LinkMode::ButtonReply => {
+            send_button_reply(ctx, msg, previous, &links, &guild_id_str, locale).await
+        },
+        utils::LinkMode::TextReply => {
+            send_text_reply(ctx, msg, previous, &links, &guild_id_str, locale).await
+        },
+        utils::LinkMode::SuppressEmbedsAndReply => {
+            if !links.is_empty() {
+                // Hide the untagged previews; the reply's links get their own
+                let _ = msg.channel_id
+                    .edit_message(&ctx.http, msg.id, EditMessage::new().suppress_embeds(true))
+                    .await;
+            }
+            send_text_reply(ctx, msg, previous, &links, &guild_id_str, locale).await
+        },
+        utils::LinkMode::WebhookRepost => {
+            let found = utils::find_affiliate_links(content, &rules);
+            if send_webhook_repost(ctx, msg, previous, content, &found, &links, &guild_id_str).await {
+                false
             } else {
-                // Mixed content: add button(s) with affiliate link(s)
-                let guild_id = msg.guild_id.map(|id| id.get().to_string());
-                let mut buttons = Vec::new();
-                let mut footer_template = String::new();
-                
-                // Process all Amazon URLs in the message
-                for (i, url) in amazon_urls.iter().enumerate() {
-                    if let Some((clean_url, template)) = utils::process_amazon_url(url, guild_id.clone()).await {
-                        // Use footer template from first successful processing
-                        if footer_template.is_empty() {
-                            footer_template = template;
-                        }
-                        
-                        // Create button label based on number of links
-                        let label = if amazon_urls.len() > 1 {
-                            format!("🛒 View on Amazon ({})", i + 1)
-                        } else {
-                            "🛒 View on Amazon".to_string()
-                        };
-                        
-                        let button = CreateButton::new_link(&clean_url).label(&label);
-                        buttons.push(button);
-                        
-                        // Discord has a limit of 5 buttons per action row
-                        if buttons.len() >= 5 {
-                            break;
-                        }
-                    }
-                }
-                
-                // Only send message if we have at least one button
-                if !buttons.is_empty() {
-                    // Construct footer with sender mention support
-                    let sender_mention = format!("<@{}>", msg.author.id.get());
-                    let footer = if footer_template.contains("{{sender}}") {
-                        footer_template.replace("{{sender}}", &sender_mention)
-                    } else {
-                        format!("{} recommended this. {}", sender_mention, footer_template)
-                    };
-                    
-                    let action_row = CreateActionRow::Buttons(buttons);
-                    
-                    let response_content = format!("-# {}", footer);
-                    let message = CreateMessage::new()
-                        .content(response_content)
-                        .components(vec![action_row]);
-                        
-                    let _ = msg.channel_id.send_message(&ctx.http, message).await;
-                }
+                // No webhook permission, attachments too large, ...: fall back to buttons
+                send_button_reply(ctx, msg, previous, &links, &guild_id_str, locale).await
             }
+        },
+        utils::LinkMode::Off => false,
+    };
+
+    if replied && dedup_mode != dedup::DedupMode::Off {
+        if let Some(reply) = replies::find(msg.id) {
+            dedup::remember(msg.channel_id, &links, reply.reply_id);
+        }
+    }
+    replied
+}
+
+/// The channel, its parent channel (for threads) and its category, most specific first.
+/// Channels come from the gateway cache; only channels missing from it are fetched.
+async fn channel_chain(ctx: &Context, channel_id: ChannelId) -> Vec<ChannelId> {
+    let mut chain = vec![channel_id];
+    // Thread → parent channel → category
+    while chain.len() < 3 {
+        let Some(&current) = chain.last() else {
+            break;
+        };
+        let parent = match current.to_channel(ctx).await {
+            Ok(Channel::Guild(channel)) => channel.parent_id,
+            _ => None,
+        };
+        match parent {
+            Some(parent) => chain.push(parent),
+            None => break,
+        }
+    }
+    chain
+}
+
+/// Language for output in the message's guild: the configured one, else the guild's Discord locale
+fn message_locale(ctx: &Context, msg: &Message) -> &'static str {
+    i18n::resolve(msg.guild_id.map(|id| id.get().to_string()).as_deref(), &guild_discord_locale(ctx, msg))
+}
+
+/// Discord locale of the guild a message was posted in, empty if unknown
+fn guild_discord_locale(ctx: &Context, msg: &Message) -> String {
+    msg.guild_id
+        .and_then(|id| ctx.cache.guild(id).map(|guild| guild.preferred_locale.clone()))
+        .unwrap_or_default()
+}
+
+/// Footer line below a reply, with `{{sender}}` replaced by a mention of the author
+fn reply_footer(msg: &Message, footer_template: &str, locale: &str) -> String {
+    let sender_mention = format!("<@{}>", msg.author.id.get());
+    let footer = if footer_template.contains("{{sender}}") {
+        footer_template.replace("{{sender}}", &sender_mention)
+    } else {
+        i18n::format(locale, "footer-recommended", &[
+            ("sender", sender_mention.into()),
+            ("footer", footer_template.into()),
+        ])
+    };
+    format!("-# {}", footer)
+}
+
+/// Post link button(s) for the cleaned links below the message and log them.
+/// Returns whether the message has a button reply afterwards.
+async fn send_button_reply(
+    ctx: &Context,
+    msg: &Message,
+    previous: Option<&replies::Reply>,
+    links: &[utils::ProcessedLink],
+    guild_id: &str,
+    locale: &str,
+) -> bool {
+    // Only send message if we have at least one button
+    let Some(first) = links.first() else {
+        return false;
+    };
+
+    let shown = &links[..links.len().min(5)];
+    let also_offer = utils::also_offer(guild_id);
+    let mut buttons = Vec::new();
+    let mut rows = Vec::new();
+    for (i, link) in shown.iter().enumerate() {
+        // The same product on the guild's "also offer" marketplaces, each with that marketplace's tag
+        let alternatives: Vec<CreateButton> = also_offer.iter()
+            .filter(|marketplace| marketplace.tld != link.region)
+            .filter_map(|marketplace| {
+                let converted = utils::convert_link(link, marketplace.tld, Some(guild_id)).ok()?;
+                Some(CreateButton::new_link(clicks::url_for(&converted, guild_id, Some(msg.id))).label(format!("{} .{}", marketplace.flag, marketplace.tld)))
+            })
+            .collect();
+
+        // Create button label based on link kind (or marketplace, next to alternatives) and number of links
+        let label = match marketplace::find(&link.region) {
+            Some(marketplace) if !alternatives.is_empty() => format!("{} .{}", marketplace.flag, marketplace.tld),
+            _ => i18n::text(locale, link.label),
+        };
+        let label = if links.len() > 1 { format!("{} ({})", label, i + 1) } else { label };
+        let button = CreateButton::new_link(clicks::url_for(link, guild_id, Some(msg.id))).label(&label);
+
+        if also_offer.is_empty() {
+            buttons.push(button);
+        } else {
+            // One row per link; Discord has a limit of 5 buttons per action row and 5 rows per message
+            rows.push(CreateActionRow::Buttons(std::iter::once(button).chain(alternatives).collect()));
         }
     }
+    if !buttons.is_empty() {
+        rows.push(CreateActionRow::Buttons(buttons));
+    }
+    log_links(shown, previous, guild_id);
+
+    let embeds = product_embeds(shown, guild_id, locale).await
+        .into_iter()
+        .flatten()
+        .collect();
+
+    // Use footer template from first successful processing
+    let content = reply_footer(msg, &first.footer_template, locale);
+    post_reply(ctx, msg, previous, content, rows, embeds, false).await
+}
+
+/// Delete the message and repost it through a webhook with every link replaced by its cleaned version.
+/// Returns false if the repost failed; the original message is then left alone.
+async fn send_webhook_repost(
+    ctx: &Context,
+    msg: &Message,
+    previous: Option<&replies::Reply>,
+    content: &str,
+    found: &[utils::FoundLink],
+    links: &[utils::ProcessedLink],
+    guild_id: &str,
+) -> bool {
+    let Some(first) = links.first() else {
+        return true;
+    };
+
+    let locale = message_locale(ctx, msg);
+    let rewritten = format!(
+        "{}\n{}",
+        utils::rewrite_content(content, found, links),
+        reply_footer(msg, &first.footer_template, locale)
+    );
+    if !webhook::repost(ctx, msg, rewritten).await {
+        return false;
+    }
+
+    log_links(links, previous, guild_id);
+    let _ = msg.delete(&ctx.http).await;
+    true
+}
+
+/// Reply to the message with the cleaned links as text and log them.
+/// Returns whether the message has a text reply afterwards.
+async fn send_text_reply(
+    ctx: &Context,
+    msg: &Message,
+    previous: Option<&replies::Reply>,
+    links: &[utils::ProcessedLink],
+    guild_id: &str,
+    locale: &str,
+) -> bool {
+    let Some(first) = links.first() else {
+        return false;
+    };
+
+    let shown = &links[..links.len().min(5)];
+    let product_embeds = product_embeds(shown, guild_id, locale).await;
+    // Links with a product embed are wrapped in <> so Discord doesn't add its own preview
+    let mut lines: Vec<String> = shown.iter()
+        .zip(&product_embeds)
+        .map(|(link, embed)| match embed {
+            Some(_) => format!("<{}>", link.clean_url),
+            None => link.clean_url.clone(),
+        })
+        .collect();
+    lines.push(reply_footer(msg, &first.footer_template, locale));
+    log_links(shown, previous, guild_id);
+
+    let embeds = product_embeds.into_iter().flatten().collect();
+    post_reply(ctx, msg, previous, lines.join("\n"), Vec::new(), embeds, true).await
+}
+
+/// Log links for the stats. After an edit, links that were already in the message are not counted again.
+fn log_links(links: &[utils::ProcessedLink], previous: Option<&replies::Reply>, guild_id: &str) {
+    for link in links {
+        if previous.is_some_and(|previous| previous.links.contains(&link.source_url)) {
+            continue;
+        }
+        utils::log_link(guild_id, link.network, &link.region);
+    }
+}
+
+/// Product embeds for links to Amazon products, one entry per link;
+/// all `None` unless the Product Advertising API is set up for the guild or operator
+async fn product_embeds(links: &[utils::ProcessedLink], guild_id: &str, locale: &str) -> Vec<Option<CreateEmbed>> {
+    futures::future::join_all(links.iter().map(|link| async move {
+        paapi::product_for(link, Some(guild_id)).await
+            .map(|product| paapi::embed(&product, &link.clean_url, locale))
+    }))
+    .await
+}
+
+/// Send a reply for the message, or update the bot's earlier reply after an edit.
+/// The reply is recorded so later edits of the message update it instead of adding another one.
+async fn post_reply(
+    ctx: &Context,
+    msg: &Message,
+    previous: Option<&replies::Reply>,
+    content: String,
+    components: Vec<CreateActionRow>,
+    embeds: Vec<CreateEmbed>,
+    reference: bool,
+) -> bool {
+    if let Some(previous) = previous {
+        let edit = EditMessage::new()
+            .content(content.clone())
+            .components(components.clone())
+            .embeds(embeds.clone());
+        if msg.channel_id.edit_message(&ctx.http, previous.reply_id, edit).await.is_ok() {
+            replies::record(msg, previous.reply_id);
+            return true;
+        }
+        // The reply was deleted in the meantime: send a new one
+    }
+
+    let mut message = CreateMessage::new().content(content).components(components).embeds(embeds);
+    if reference {
+        message = message.reference_message(msg);
+    }
+    match msg.channel_id.send_message(&ctx.http, message).await {
+        Ok(sent) => {
+            replies::record(msg, sent.id);
+            true
+        },
+        Err(_) => false,
+    }
+}
+
+/// Send a short notice to the message's channel and delete it again after the guild's hint lifetime
+async fn send_temporary_notice(ctx: &Context, msg: &Message, content: String) {
+    let message = CreateMessage::new().content(content);
+    if let Ok(sent) = msg.channel_id.send_message(&ctx.http, message).await {
+        let guild_id = msg.guild_id.map(|id| id.get().to_string()).unwrap_or_default();
+        cleanup::delete_later(&ctx.http, sent.channel_id, sent.id, utils::hint_lifetime(&guild_id));
+    }
+}
+
+/// Report a message with third-party affiliate tags to the guild's mod channel
+async fn report_foreign_tags(ctx: &Context, msg: &Message, foreign: &[&providers::Attribution], locale: &str) {
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    let Some(channel_id) = db::guild_setting(&guild_id.get().to_string(), "mod_channel_id")
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return;
+    };
+
+    let details = foreign.iter()
+        .map(|attribution| format!("• `{}`", attribution.summary()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let content = format!(
+        "{}\n{}",
+        i18n::format(locale, "foreign-tag-report", &[
+            ("sender", msg.author.id.mention().to_string().into()),
+            ("channel", msg.channel_id.mention().to_string().into()),
+            ("link", msg.link().into()),
+        ]),
+        details
+    );
+    let _ = ChannelId::new(channel_id)
+        .send_message(&ctx.http, CreateMessage::new().content(content))
+        .await;
 }
 
 #[tokio::main]
@@ -158,6 +577,8 @@ async fn main() {
     config::init().expect("Failed to load .env");
     // Initialize SQLite database
     db::init().expect("Failed to initialize database");
+    // Serve click-tracking short links if a public base URL is configured
+    clicks::start();
     // Retrieve Discord token from environment
     let token = config::discord_token();
     // Define the necessary gateway intents (including DM support)
@@ -0,0 +1,113 @@
+// src/marketplace.rs
+// Central registry of Amazon marketplaces used by the parser, autocomplete, env defaults and stats.
+
+/// A single Amazon marketplace, identified by the part of the domain after "amazon."
+pub struct Marketplace {
+    /// Domain suffix after "amazon.", e.g. "de", "co.uk", "com.au"
+    pub tld: &'static str,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: &'static str,
+    pub flag: &'static str,
+    pub name: &'static str,
+    /// Suffix Amazon appends to Associates tracking tags in this marketplace, e.g. "-21"
+    pub tag_suffix: Option<&'static str>,
+    /// Whether an Amazon Associates program exists for this marketplace
+    pub associates: bool,
+}
+
+pub static MARKETPLACES: &[Marketplace] = &[
+    // North America
+    Marketplace { tld: "com",    country: "US", flag: "🇺🇸", name: "USA",          tag_suffix: Some("-20"), associates: true },
+    Marketplace { tld: "ca",     country: "CA", flag: "🇨🇦", name: "Canada",       tag_suffix: Some("-20"), associates: true },
+    Marketplace { tld: "com.mx", country: "MX", flag: "🇲🇽", name: "Mexico",       tag_suffix: Some("-20"), associates: true },
+    // South America
+    Marketplace { tld: "com.br", country: "BR", flag: "🇧🇷", name: "Brazil",       tag_suffix: Some("-20"), associates: true },
+    Marketplace { tld: "cl",     country: "CL", flag: "🇨🇱", name: "Chile",        tag_suffix: None,        associates: false },
+    // Europe
+    Marketplace { tld: "co.uk",  country: "GB", flag: "🇬🇧", name: "UK",           tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "de",     country: "DE", flag: "🇩🇪", name: "Germany",      tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "fr",     country: "FR", flag: "🇫🇷", name: "France",       tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "es",     country: "ES", flag: "🇪🇸", name: "Spain",        tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "it",     country: "IT", flag: "🇮🇹", name: "Italy",        tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "nl",     country: "NL", flag: "🇳🇱", name: "Netherlands",  tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "com.be", country: "BE", flag: "🇧🇪", name: "Belgium",      tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "se",     country: "SE", flag: "🇸🇪", name: "Sweden",       tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "pl",     country: "PL", flag: "🇵🇱", name: "Poland",       tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "ie",     country: "IE", flag: "🇮🇪", name: "Ireland",      tag_suffix: None,        associates: false },
+    Marketplace { tld: "com.tr", country: "TR", flag: "🇹🇷", name: "Turkey",       tag_suffix: Some("-21"), associates: true },
+    // Middle East & Africa
+    Marketplace { tld: "ae",     country: "AE", flag: "🇦🇪", name: "UAE",          tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "sa",     country: "SA", flag: "🇸🇦", name: "Saudi Arabia", tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "eg",     country: "EG", flag: "🇪🇬", name: "Egypt",        tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "co.za",  country: "ZA", flag: "🇿🇦", name: "South Africa", tag_suffix: Some("-21"), associates: true },
+    // Asia
+    Marketplace { tld: "in",     country: "IN", flag: "🇮🇳", name: "India",        tag_suffix: Some("-21"), associates: true },
+    Marketplace { tld: "co.jp",  country: "JP", flag: "🇯🇵", name: "Japan",        tag_suffix: Some("-22"), associates: true },
+    Marketplace { tld: "sg",     country: "SG", flag: "🇸🇬", name: "Singapore",    tag_suffix: Some("-22"), associates: true },
+    Marketplace { tld: "cn",     country: "CN", flag: "🇨🇳", name: "China",        tag_suffix: None,        associates: false },
+    // Oceania
+    Marketplace { tld: "com.au", country: "AU", flag: "🇦🇺", name: "Australia",    tag_suffix: Some("-22"), associates: true },
+];
+
+/// Hosts Amazon uses for share links; these redirect to a marketplace URL
+pub static SHORT_LINK_HOSTS: &[&str] = &["amzn.to", "amzn.eu", "amzn.asia", "amzn.com", "a.co"];
+
+impl Marketplace {
+    /// Bare domain, e.g. "amazon.co.uk"
+    pub fn domain(&self) -> String {
+        format!("amazon.{}", self.tld)
+    }
+
+    /// Human readable label for autocomplete and stats, e.g. "🇬🇧 UK (amazon.co.uk)"
+    pub fn label(&self) -> String {
+        format!("{} {} ({})", self.flag, self.name, self.domain())
+    }
+
+    /// Environment key holding the developer fallback tag, e.g. "DEFAULT_TRACKING_TAG_CO_UK"
+    pub fn env_key(&self) -> String {
+        format!("DEFAULT_TRACKING_TAG_{}", self.tld.to_uppercase().replace('.', "_"))
+    }
+}
+
+/// Look up a marketplace by its TLD ("de", "co.uk", ...)
+pub fn find(tld: &str) -> Option<&'static Marketplace> {
+    let tld = tld.trim().trim_start_matches('.').to_lowercase();
+    MARKETPLACES.iter().find(|m| m.tld == tld)
+}
+
+/// Look up a marketplace by URL host, accepting any subdomain
+/// ("www.amazon.de", "smile.amazon.com", "amazon.co.uk"). Unknown hosts return None.
+pub fn from_host(host: &str) -> Option<&'static Marketplace> {
+    let host = host.trim_end_matches('.').to_lowercase();
+    MARKETPLACES.iter().find(|m| {
+        let domain = m.domain();
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// Check whether a host is one of Amazon's short-link hosts (optionally with "www.")
+pub fn is_short_link_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    SHORT_LINK_HOSTS.contains(&host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_link_hosts_are_detected() {
+        for host in ["amzn.to", "amzn.eu", "amzn.asia", "amzn.com", "a.co", "www.amzn.eu", "AMZN.ASIA", "a.co."] {
+            assert!(is_short_link_host(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn lookalike_hosts_are_no_short_link_hosts() {
+        for host in ["amzn.com.evil.example", "evil-amzn.com", "amzn.company", "xa.co", "a.co.evil.example", "amzn.eu.org"] {
+            assert!(!is_short_link_host(host), "{host}");
+            assert!(from_host(host).is_none(), "{host}");
+        }
+    }
+}
@@ -0,0 +1,464 @@
+// src/paapi.rs
+// Optional Product Advertising API 5.0 client: looks up title, image, price, rating and Prime
+// eligibility of Amazon products for rich embeds. Requests are signed with AWS Signature Version 4,
+// results are cached in SQLite. Without credentials (per guild or operator) nothing is requested.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+use reqwest::Client;
+use ring::hmac;
+use rusqlite::params;
+use serenity::all::{CreateEmbed, CreateEmbedFooter, Timestamp};
+use url::Url;
+use super::{config, db, i18n, marketplace, providers, utils};
+
+const SERVICE: &str = "ProductAdvertisingAPI";
+const PATH: &str = "/paapi5/getitems";
+const TARGET: &str = "com.amazon.paapi5.v1.ProductAdvertisingAPIv1.GetItems";
+/// Lookups hold back the bot's replies in chat and `/amazon` answers, so a slow API is given up on early
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// Failed lookups are not retried for this long
+const NEGATIVE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Amazon's brand color for embeds
+const EMBED_COLOR: u32 = 0xFF9900;
+
+/// Requested response fields
+const RESOURCES: &[&str] = &[
+    "ItemInfo.Title",
+    "Images.Primary.Large",
+    "Offers.Listings.Price",
+    "Offers.Listings.DeliveryInfo.IsPrimeEligible",
+    "CustomerReviews.StarRating",
+    "CustomerReviews.Count",
+];
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// Access key pair and the Associates tag registered with it
+pub struct Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub partner_tag: String,
+}
+
+/// Product details for an embed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub title: String,
+    pub image_url: Option<String>,
+    /// Formatted price of the buy-box offer, e.g. "29,99 €"
+    pub price: Option<String>,
+    /// The same price as a number in the marketplace's currency
+    pub amount: Option<f64>,
+    pub prime: bool,
+    /// Average star rating out of 5
+    pub rating: Option<f64>,
+    pub review_count: Option<u64>,
+    /// Unix time of the lookup; prices must be shown with their date
+    pub fetched_at: i64,
+}
+
+/// AWS region serving a marketplace's Product Advertising API
+fn aws_region(tld: &str) -> Option<&'static str> {
+    match tld {
+        "com" | "ca" | "com.mx" | "com.br" => Some("us-east-1"),
+        "co.uk" | "de" | "fr" | "it" | "es" | "nl" | "se" | "pl" | "com.be" | "com.tr" | "ae" | "sa" | "eg" | "in" => Some("eu-west-1"),
+        "co.jp" | "sg" | "com.au" => Some("us-west-2"),
+        _ => None,
+    }
+}
+
+/// Credentials for lookups in a guild: the guild's own, otherwise the operator's with the default tag
+pub fn credentials(guild_id: Option<&str>, region: &str) -> Option<Credentials> {
+    let own = guild_id.and_then(|guild_id| {
+        db::with_connection(|conn| {
+            conn.query_row(
+                "SELECT access_key, secret_key, partner_tag FROM guild_paapi_credentials WHERE guild_id = ?",
+                params![guild_id],
+                |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, Option<String>>(2)?)),
+            )
+        }).ok()
+    });
+    if let Some((access_key, secret_key, partner_tag)) = own {
+        // Without an explicit partner tag the guild's Amazon tag for the region is used
+        let partner_tag = partner_tag.filter(|tag| !tag.is_empty())
+            .unwrap_or_else(|| utils::tag_and_footer(guild_id, &providers::amazon::Amazon, region).0);
+        return Some(Credentials { access_key, secret_key, partner_tag });
+    }
+
+    let (access_key, secret_key) = config::paapi_credentials()?;
+    let partner_tag = config::default_tracking_tag(marketplace::find(region)?);
+    if partner_tag.is_empty() {
+        return None;
+    }
+    Some(Credentials { access_key, secret_key, partner_tag })
+}
+
+/// Store a guild's own credentials as (access key, secret key, partner tag); without a secret key
+/// the stored one is kept. `None` removes them so the operator's are used again.
+pub fn set_guild_credentials(guild_id: &str, credentials: Option<(&str, Option<&str>, Option<&str>)>) -> rusqlite::Result<()> {
+    db::with_connection(|conn| {
+        match credentials {
+            Some((access_key, Some(secret_key), partner_tag)) => conn.execute(
+                "INSERT OR REPLACE INTO guild_paapi_credentials (guild_id, access_key, secret_key, partner_tag) VALUES (?, ?, ?, ?)",
+                params![guild_id, access_key, secret_key, partner_tag],
+            ),
+            Some((access_key, None, partner_tag)) => conn.execute(
+                "UPDATE guild_paapi_credentials SET access_key = ?, partner_tag = ? WHERE guild_id = ?",
+                params![access_key, partner_tag, guild_id],
+            ),
+            None => conn.execute("DELETE FROM guild_paapi_credentials WHERE guild_id = ?", params![guild_id]),
+        }?;
+        // Cached failures may have been caused by the old credentials
+        conn.execute("DELETE FROM product_cache WHERE title IS NULL", [])?;
+        Ok(())
+    })
+}
+
+/// Access key and partner tag of a guild's own credentials, if it has any; the secret key never leaves the database
+pub fn guild_credentials(guild_id: &str) -> Option<(String, Option<String>)> {
+    db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT access_key, partner_tag FROM guild_paapi_credentials WHERE guild_id = ?",
+            params![guild_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+    }).ok()
+}
+
+/// Product details for a cleaned link; `None` for links other than Amazon products,
+/// without credentials or if the lookup failed
+pub async fn product_for(link: &utils::ProcessedLink, guild_id: Option<&str>) -> Option<Product> {
+    let asin = link.item_id.as_deref().filter(|_| link.network == "amazon")?;
+    lookup(guild_id, &link.region, asin).await
+}
+
+/// Look up a product on a marketplace, using the cache when possible
+pub async fn lookup(guild_id: Option<&str>, region: &str, asin: &str) -> Option<Product> {
+    let aws_region = aws_region(region)?;
+    let credentials = credentials(guild_id, region)?;
+    if let Some(cached) = cached(region, asin) {
+        return cached;
+    }
+
+    let product = get_item(&credentials, aws_region, region, asin).await;
+    store(region, asin, product.as_ref());
+    product
+}
+
+/// Cache entry within its TTL; `Some(None)` for a recently failed lookup
+fn cached(region: &str, asin: &str) -> Option<Option<Product>> {
+    let (title, image_url, price, amount, prime, rating, review_count, fetched_at) = db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT title, image_url, price, amount, prime, rating, review_count, fetched_at FROM product_cache WHERE region = ? AND asin = ?",
+            params![region, asin],
+            |r| Ok((
+                r.get::<_, Option<String>>(0)?,
+                r.get::<_, Option<String>>(1)?,
+                r.get::<_, Option<String>>(2)?,
+                r.get::<_, Option<f64>>(3)?,
+                r.get::<_, bool>(4)?,
+                r.get::<_, Option<f64>>(5)?,
+                r.get::<_, Option<i64>>(6)?,
+                r.get::<_, i64>(7)?,
+            )),
+        )
+    }).ok()?;
+
+    let age = chrono::Utc::now().timestamp() - fetched_at;
+    match title {
+        Some(title) if age < config::product_cache_ttl().as_secs() as i64 => Some(Some(Product {
+            title,
+            image_url,
+            price,
+            amount,
+            prime,
+            rating,
+            review_count: review_count.map(|count| count as u64),
+            fetched_at,
+        })),
+        None if age < NEGATIVE_TTL.as_secs() as i64 => Some(None),
+        _ => None,
+    }
+}
+
+/// Remember a lookup; failures are stored without a title
+fn store(region: &str, asin: &str, product: Option<&Product>) {
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO product_cache (region, asin, title, image_url, price, amount, prime, rating, review_count, fetched_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                region,
+                asin,
+                product.map(|p| p.title.as_str()),
+                product.and_then(|p| p.image_url.as_deref()),
+                product.and_then(|p| p.price.as_deref()),
+                product.and_then(|p| p.amount),
+                product.is_some_and(|p| p.prime),
+                product.and_then(|p| p.rating),
+                product.and_then(|p| p.review_count).map(|count| count as i64),
+                product.map_or_else(|| chrono::Utc::now().timestamp(), |p| p.fetched_at),
+            ],
+        )
+    });
+}
+
+/// Signed GetItems request for a single ASIN
+async fn get_item(credentials: &Credentials, aws_region: &str, region: &str, asin: &str) -> Option<Product> {
+    let host = format!("webservices.amazon.{}", region);
+    let endpoint = config::paapi_endpoint().unwrap_or_else(|| format!("https://{}", host));
+    let url = Url::parse(&format!("{}{}", endpoint, PATH)).ok()?;
+    // The signed host must be the one the request goes to, including a non-default port
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => return None,
+    };
+
+    let payload = serde_json::json!({
+        "ItemIds": [asin],
+        "ItemIdType": "ASIN",
+        "PartnerTag": credentials.partner_tag,
+        "PartnerType": "Associates",
+        "Marketplace": format!("www.amazon.{}", region),
+        "Resources": RESOURCES,
+    })
+    .to_string();
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let headers = [
+        ("content-encoding", "amz-1.0".to_string()),
+        ("content-type", "application/json; charset=utf-8".to_string()),
+        ("host", host),
+        ("x-amz-date", amz_date.clone()),
+        ("x-amz-target", TARGET.to_string()),
+    ];
+    // Signed with the path the request goes to, including a path prefix of `PAAPI_ENDPOINT`
+    let authorization = authorization(credentials, aws_region, SERVICE, "POST", url.path(), &headers, &payload, &amz_date);
+
+    let mut request = CLIENT.post(url).header("authorization", authorization).body(payload);
+    for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+        request = request.header(*name, value);
+    }
+    let body: serde_json::Value = request.send().await.ok()?.json().await.ok()?;
+    parse_item(&body, now.timestamp())
+}
+
+/// Product details from a GetItems response; `None` if the item is missing (e.g. an `Errors` response)
+fn parse_item(body: &serde_json::Value, fetched_at: i64) -> Option<Product> {
+    let item = body.pointer("/ItemsResult/Items/0")?;
+    let listing = item.pointer("/Offers/Listings/0");
+    Some(Product {
+        title: item.pointer("/ItemInfo/Title/DisplayValue")?.as_str()?.to_string(),
+        image_url: item.pointer("/Images/Primary/Large/URL").and_then(|v| v.as_str()).map(str::to_string),
+        price: listing
+            .and_then(|l| l.pointer("/Price/DisplayAmount"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        amount: listing
+            .and_then(|l| l.pointer("/Price/Amount"))
+            .and_then(|v| v.as_f64()),
+        prime: listing
+            .and_then(|l| l.pointer("/DeliveryInfo/IsPrimeEligible"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        rating: item.pointer("/CustomerReviews/StarRating/Value").and_then(|v| v.as_f64()),
+        review_count: item.pointer("/CustomerReviews/Count").and_then(|v| v.as_u64()),
+        fetched_at,
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes()).as_ref().to_vec()
+}
+
+/// `Authorization` header of an AWS Signature Version 4 request without query string.
+/// `headers` are the signed headers with lowercase names, sorted by name.
+#[allow(clippy::too_many_arguments)]
+fn authorization(
+    credentials: &Credentials,
+    aws_region: &str,
+    service: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, String)],
+    payload: &str,
+    amz_date: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let canonical_headers: String = headers.iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, utils::sha256_hex(payload.as_bytes())
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, aws_region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date, scope, utils::sha256_hex(canonical_request.as_bytes())
+    );
+
+    let key = hmac_sha256(format!("AWS4{}", credentials.secret_key).as_bytes(), date);
+    let key = hmac_sha256(&key, aws_region);
+    let key = hmac_sha256(&key, service);
+    let key = hmac_sha256(&key, "aws4_request");
+    let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key, scope, signed_headers, signature
+    )
+}
+
+/// Embed with title, image, price, rating and Prime badge, linking to the cleaned URL
+pub fn embed(product: &Product, clean_url: &str, locale: &str) -> CreateEmbed {
+    let title: String = if product.title.chars().count() > 256 {
+        product.title.chars().take(255).chain(std::iter::once('…')).collect()
+    } else {
+        product.title.clone()
+    };
+
+    let mut lines = Vec::new();
+    match (&product.price, product.prime) {
+        (Some(price), true) => lines.push(format!("**{}** · ✔️ Prime", price)),
+        (Some(price), false) => lines.push(format!("**{}**", price)),
+        (None, true) => lines.push("✔️ Prime".to_string()),
+        (None, false) => {},
+    }
+    if let Some(rating) = product.rating {
+        let reviews = product.review_count
+            .map(|count| format!(" ({})", i18n::format(locale, "product-ratings", &[("count", count.into())])))
+            .unwrap_or_default();
+        lines.push(format!("⭐ {:.1}/5{}", rating, reviews));
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title(title)
+        .url(clean_url)
+        .color(EMBED_COLOR)
+        .footer(CreateEmbedFooter::new(i18n::text(locale, "product-disclaimer")));
+    if !lines.is_empty() {
+        embed = embed.description(lines.join("\n"));
+    }
+    if let Some(image_url) = &product.image_url {
+        embed = embed.thumbnail(image_url);
+    }
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp(product.fetched_at) {
+        embed = embed.timestamp(timestamp);
+    }
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_credentials() -> Credentials {
+        // Example keys of AWS's Signature Version 4 test suite
+        Credentials {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            partner_tag: String::new(),
+        }
+    }
+
+    fn example_authorization(method: &str, headers: &[(&str, String)], payload: &str) -> String {
+        authorization(&example_credentials(), "us-east-1", "service", method, "/", headers, payload, "20150830T123600Z")
+    }
+
+    #[test]
+    fn signatures_match_the_aws_test_suite() {
+        let host = ("host", "example.amazonaws.com".to_string());
+        let date = ("x-amz-date", "20150830T123600Z".to_string());
+
+        // get-vanilla
+        assert_eq!(
+            example_authorization("GET", &[host.clone(), date.clone()], ""),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        // post-vanilla
+        assert_eq!(
+            example_authorization("POST", &[host.clone(), date.clone()], ""),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        );
+        // post-x-www-form-urlencoded
+        let content_type = ("content-type", "application/x-www-form-urlencoded".to_string());
+        assert_eq!(
+            example_authorization("POST", &[content_type, host, date], "Param1=value1"),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        );
+    }
+
+    /// GetItems response in the shape documented for PA-API 5.0, with the requested `RESOURCES`
+    const GET_ITEMS_RESPONSE: &str = r#"{
+        "ItemsResult": {
+            "Items": [{
+                "ASIN": "B08N5WRWNW",
+                "DetailPageURL": "https://www.amazon.de/dp/B08N5WRWNW?tag=example-21&linkCode=ogi&th=1&psc=1",
+                "CustomerReviews": {
+                    "Count": 12873,
+                    "StarRating": { "Value": 4.6 }
+                },
+                "Images": {
+                    "Primary": {
+                        "Large": { "Height": 500, "URL": "https://m.media-amazon.com/images/I/61Xyz2abcdL._SL500_.jpg", "Width": 500 }
+                    }
+                },
+                "ItemInfo": {
+                    "Title": { "DisplayValue": "Echo Dot (4th generation)", "Label": "Title", "Locale": "de_DE" }
+                },
+                "Offers": {
+                    "Listings": [{
+                        "DeliveryInfo": { "IsPrimeEligible": true },
+                        "Id": "abc123",
+                        "Price": { "Amount": 29.99, "Currency": "EUR", "DisplayAmount": "29,99 €" },
+                        "ViolatesMAP": false
+                    }]
+                }
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn items_are_parsed_from_get_items_responses() {
+        let body: serde_json::Value = serde_json::from_str(GET_ITEMS_RESPONSE).unwrap();
+        assert_eq!(parse_item(&body, 1_700_000_000), Some(Product {
+            title: "Echo Dot (4th generation)".to_string(),
+            image_url: Some("https://m.media-amazon.com/images/I/61Xyz2abcdL._SL500_.jpg".to_string()),
+            price: Some("29,99 €".to_string()),
+            amount: Some(29.99),
+            prime: true,
+            rating: Some(4.6),
+            review_count: Some(12873),
+            fetched_at: 1_700_000_000,
+        }));
+    }
+
+    #[test]
+    fn error_responses_have_no_item() {
+        let body = serde_json::json!({
+            "Errors": [{ "Code": "ItemNotAccessible", "Message": "The ItemId B08N5WRWNW is not accessible through the Product Advertising API." }]
+        });
+        assert_eq!(parse_item(&body, 0), None);
+    }
+}
@@ -0,0 +1,54 @@
+// src/prices.rs
+// Where price watches get current prices from: the Product Advertising API,
+// or a local JSON file for development and tests (PRICE_SOURCE=stub).
+
+use std::collections::HashMap;
+use super::{config, paapi};
+
+/// A product's current price
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    /// Amount in the marketplace's currency
+    pub amount: f64,
+    /// Formatted for display, e.g. "29,99 €"
+    pub display: String,
+}
+
+/// Configured price source (PRICE_SOURCE)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriceSource {
+    /// Product Advertising API with the guild's or operator's credentials (default)
+    ProductApi,
+    /// JSON file mapping "{region}:{asin}" to a price, e.g. `{"de:B08N5WRWNW": 24.99}` (PRICE_STUB_FILE)
+    Stub(String),
+}
+
+impl PriceSource {
+    pub fn from_config() -> Self {
+        match config::price_source().as_str() {
+            "stub" => Self::Stub(config::price_stub_file()),
+            _ => Self::ProductApi,
+        }
+    }
+
+    /// Current price of a product on a marketplace; `None` if it is unknown or unavailable
+    pub async fn current_price(&self, guild_id: Option<&str>, region: &str, asin: &str) -> Option<Price> {
+        match self {
+            Self::ProductApi => {
+                let product = paapi::lookup(guild_id, region, asin).await?;
+                let amount = product.amount?;
+                Some(Price {
+                    display: product.price.unwrap_or_else(|| format!("{:.2}", amount)),
+                    amount,
+                })
+            },
+            Self::Stub(path) => {
+                // Read on every check so prices can be changed while the bot runs
+                let data = tokio::fs::read_to_string(path).await.ok()?;
+                let prices: HashMap<String, f64> = serde_json::from_str(&data).ok()?;
+                let amount = *prices.get(&format!("{}:{}", region, asin))?;
+                Some(Price { amount, display: format!("{:.2}", amount) })
+            },
+        }
+    }
+}
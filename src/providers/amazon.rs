@@ -0,0 +1,325 @@
+// src/providers/amazon.rs
+// Amazon Associates: marketplace links, share links and the supported non-product pages.
+
+use regex::Regex;
+use url::Url;
+use std::sync::LazyLock;
+use super::{AffiliateProvider, ParsedLink};
+use super::super::config;
+use super::super::marketplace::{self, Marketplace};
+
+/// Product path shapes that carry an ASIN, e.g. `/dp/B0..`, `/gp/product/B0..`,
+/// `/gp/aw/d/B0..` (mobile), `/exec/obidos/ASIN/B0..`, `/o/B0..` or `/product-reviews/B0..`.
+/// ASINs are 10 characters; book ASINs are ISBN-10s and may end in `X`. `/o/` only counts at the
+/// start of the path, elsewhere it is part of other pages (`/stores/page/o/...`).
+static ASIN_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:^/o(?:/asin)?|/(?:dp(?:/product)?|gp/product|gp/aw/d|gp/offer-listing|gp/product-reviews|product-reviews|exec/obidos/asin|exec/obidos/tg/detail/-))/([A-Z0-9]{10})(?:[/?#]|$)"
+    ).unwrap()
+});
+
+/// Check whether a string looks like an ASIN (or ISBN-10)
+fn is_asin(candidate: &str) -> bool {
+    candidate.len() == 10 && candidate.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Parse an Amazon URL and return (ASIN, marketplace)
+/// Hosts that are not a known Amazon marketplace are rejected.
+/// The ASIN is always returned uppercase.
+pub fn parse_amazon_url(url_str: &str) -> Option<(String, &'static Marketplace)> {
+    let url = Url::parse(url_str).ok()?;
+    // e.g. "www.amazon.co.uk" or "smile.amazon.com"
+    let marketplace = marketplace::from_host(url.host_str()?)?;
+
+    // capture the ASIN from a known product path segment
+    let from_path = ASIN_PATH_RE.captures(url.path())
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str().to_string());
+
+    // fall back to an "?asin=" query parameter
+    let asin = from_path.or_else(|| {
+        url.query_pairs()
+            .find(|(key, value)| key.eq_ignore_ascii_case("asin") && is_asin(value))
+            .map(|(_, value)| value.into_owned())
+    })?;
+
+    Some((asin.to_uppercase(), marketplace))
+}
+
+/// What an Amazon link points to; every kind has its own cleaned path in `clean_path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Product detail page, rebuilt as `/dp/{ASIN}/`
+    Product(String),
+    /// Search results (`/s?k=...`), rebuilt as `/s` with keywords and category filters
+    Search,
+    /// Brand store (`/stores/...`), keeps the store path
+    Store(String),
+    /// Wishlist (`/hz/wishlist/ls/{ID}`), rebuilt from the list ID
+    Wishlist(String),
+    /// Idea list (`/ideas/...`), keeps the list path
+    IdeaList(String),
+    /// Deals page (`/deals`, `/gp/goldbox`), rebuilt as `/deals`
+    Deals,
+}
+
+impl LinkKind {
+    /// Catalog message of the label for the link button in chat
+    pub fn button_label(&self) -> &'static str {
+        match self {
+            LinkKind::Product(_) => "button-amazon-product",
+            LinkKind::Search => "button-amazon-search",
+            LinkKind::Store(_) => "button-amazon-store",
+            LinkKind::Wishlist(_) => "button-amazon-wishlist",
+            LinkKind::IdeaList(_) => "button-amazon-idea-list",
+            LinkKind::Deals => "button-amazon-deals",
+        }
+    }
+
+    /// Query parameters that belong to the page itself and are always kept
+    pub fn intrinsic_params(&self) -> &'static [&'static str] {
+        match self {
+            LinkKind::Search => &["k", "i", "rh", "s"],
+            _ => &[],
+        }
+    }
+
+    /// Path of the cleaned link, without `ref=` segments or query
+    pub fn clean_path(&self) -> String {
+        match self {
+            LinkKind::Product(asin) => format!("/dp/{}/", asin),
+            LinkKind::Search => "/s".to_string(),
+            LinkKind::Store(path) | LinkKind::IdeaList(path) => path.clone(),
+            LinkKind::Wishlist(id) => format!("/hz/wishlist/ls/{}", id),
+            LinkKind::Deals => "/deals".to_string(),
+        }
+    }
+}
+
+/// Wishlist paths: `/hz/wishlist/ls/{ID}` and the legacy `/gp/registry/wishlist/{ID}`
+static WISHLIST_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^/(?:hz/wishlist/ls|gp/registry/wishlist)/([A-Z0-9]+)").unwrap()
+});
+
+/// Drop `ref=...` segments and trailing slashes from a path that is kept as-is
+fn strip_ref_segments(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty() && !segment.starts_with("ref="))
+        .fold(String::new(), |acc, segment| format!("{}/{}", acc, segment))
+}
+
+/// Classify an Amazon marketplace URL into a product or one of the supported page kinds.
+/// Returns None for unknown hosts and pages we cannot tag (cart, account, ...).
+pub fn classify_amazon_url(url_str: &str) -> Option<(LinkKind, &'static Marketplace)> {
+    if let Some((asin, marketplace)) = parse_amazon_url(url_str) {
+        return Some((LinkKind::Product(asin), marketplace));
+    }
+
+    let url = Url::parse(url_str).ok()?;
+    let marketplace = marketplace::from_host(url.host_str()?)?;
+    let path = url.path();
+    let lower = path.to_lowercase();
+
+    let kind = if lower == "/s" || lower.starts_with("/s/") {
+        // Search without keywords or category is just the home page
+        let has_query = url.query_pairs().any(|(key, _)| LinkKind::Search.intrinsic_params().contains(&key.as_ref()));
+        if !has_query {
+            return None;
+        }
+        LinkKind::Search
+    } else if lower.starts_with("/stores/") {
+        LinkKind::Store(strip_ref_segments(path))
+    } else if let Some(cap) = WISHLIST_RE.captures(path) {
+        LinkKind::Wishlist(cap[1].to_uppercase())
+    } else if lower.starts_with("/ideas/") {
+        LinkKind::IdeaList(strip_ref_segments(path))
+    } else if lower == "/deals" || lower.starts_with("/deals/") || lower.starts_with("/gp/goldbox") {
+        LinkKind::Deals
+    } else {
+        return None;
+    };
+
+    Some((kind, marketplace))
+}
+
+/// Amazon Associates across all marketplaces in `marketplace::MARKETPLACES`
+pub struct Amazon;
+
+impl AffiliateProvider for Amazon {
+    fn id(&self) -> &'static str {
+        "amazon"
+    }
+
+    fn name(&self) -> &'static str {
+        "Amazon"
+    }
+
+    fn bare_link_pattern(&self) -> &'static str {
+        r"(?:www\.)?amazon\.[a-z]{2,3}(?:\.[a-z]{2})?|(?:amzn\.(?:to|eu|asia|com)|a\.co)/"
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        marketplace::from_host(host).is_some() || marketplace::is_short_link_host(host)
+    }
+
+    fn is_short_link_host(&self, host: &str) -> bool {
+        marketplace::is_short_link_host(host)
+    }
+
+    fn parse(&self, url: &Url) -> Option<ParsedLink> {
+        let (kind, marketplace) = classify_amazon_url(url.as_str())?;
+        Some(ParsedLink {
+            region: marketplace.tld.to_string(),
+            label: kind.button_label(),
+            base_url: format!("https://{}{}", marketplace.domain(), kind.clean_path()),
+            intrinsic_params: kind.intrinsic_params(),
+            item_id: match &kind {
+                LinkKind::Product(asin) => Some(asin.clone()),
+                _ => None,
+            },
+        })
+    }
+
+    fn attribution_params(&self) -> &'static [&'static str] {
+        // `linkCode` is added by SiteStripe and link builders, `ascsubtag` by publishers and link networks
+        &["tag", "linkCode", "ascsubtag"]
+    }
+
+    /// `/ref=...` path segments; Amazon uses these for internal navigation too
+    fn attribution_hints(&self, url: &Url) -> Vec<(String, String)> {
+        url.path_segments()
+            .and_then(|mut segments| segments.find(|s| s.starts_with("ref=")))
+            .map(|s| vec![("ref".to_string(), s.trim_start_matches("ref=").to_string())])
+            .unwrap_or_default()
+    }
+
+    fn tracking_params(&self, _region: &str, tag: &str) -> Vec<(String, String)> {
+        vec![("tag".to_string(), tag.to_string())]
+    }
+
+    fn default_tag(&self, region: &str) -> String {
+        marketplace::find(region)
+            .map(config::default_tracking_tag)
+            .unwrap_or_default()
+    }
+
+    fn region_label(&self, region: &str) -> String {
+        match marketplace::find(region) {
+            Some(m) => format!("{} **{}** ({})", m.flag, m.name, m.domain()),
+            None => format!("**{}**", region),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_url_shapes_yield_the_asin() {
+        for (url, asin, tld) in [
+            ("https://www.amazon.de/dp/B08N5WRWNW", "B08N5WRWNW", "de"),
+            ("https://www.amazon.com/dp/product/B08N5WRWNW/", "B08N5WRWNW", "com"),
+            ("https://www.amazon.co.uk/gp/product/B08N5WRWNW?psc=1", "B08N5WRWNW", "co.uk"),
+            ("https://www.amazon.com/gp/aw/d/B08N5WRWNW/ref=ox_sc_act_title_1", "B08N5WRWNW", "com"),
+            ("https://www.amazon.com/exec/obidos/ASIN/B08N5WRWNW/", "B08N5WRWNW", "com"),
+            ("https://www.amazon.fr/o/B08N5WRWNW", "B08N5WRWNW", "fr"),
+            ("https://www.amazon.fr/o/ASIN/B08N5WRWNW", "B08N5WRWNW", "fr"),
+            ("https://www.amazon.de/product-reviews/B08N5WRWNW/ref=cm_cr_dp_d_show_all", "B08N5WRWNW", "de"),
+            ("https://www.amazon.de/gp/offer-listing/B08N5WRWNW", "B08N5WRWNW", "de"),
+            ("https://www.amazon.com/gp/help?asin=b08n5wrwnw", "B08N5WRWNW", "com"),
+            ("https://www.amazon.de/dp/342615540x", "342615540X", "de"),
+            ("https://www.amazon.de/Some-Desk-Lamp-Black/dp/B08N5WRWNW/ref=sr_1_1?keywords=lamp", "B08N5WRWNW", "de"),
+            ("https://smile.amazon.com/dp/B08N5WRWNW", "B08N5WRWNW", "com"),
+        ] {
+            let (found, marketplace) = parse_amazon_url(url).unwrap_or_else(|| panic!("{url}"));
+            assert_eq!((found.as_str(), marketplace.tld), (asin, tld), "{url}");
+        }
+    }
+
+    #[test]
+    fn other_urls_yield_no_asin() {
+        for url in [
+            // Not a marketplace
+            "https://amazon.example.com/dp/B08N5WRWNW",
+            "https://www.amazon.de.evil.example/dp/B08N5WRWNW",
+            "https://www.amazon.xyz/dp/B08N5WRWNW",
+            "https://www.example.com/dp/B08N5WRWNW",
+            // No or no valid ASIN
+            "https://www.amazon.de/dp/B08N5WRW",
+            "https://www.amazon.de/dp/B08N5WRWNW1",
+            "https://www.amazon.de/gp/help?asin=B08N5",
+            // `/o/` inside another page
+            "https://www.amazon.com/stores/page/o/ABCDEFGHIJ",
+        ] {
+            assert!(parse_amazon_url(url).is_none(), "{url}");
+        }
+    }
+
+    #[test]
+    fn pages_are_classified_and_cleaned() {
+        for (url, kind, path) in [
+            ("https://www.amazon.de/s?k=desk+lamp&ref=nb_sb_noss", LinkKind::Search, "/s"),
+            ("https://www.amazon.de/s/ref=sr_pg_2?rh=n%3A123", LinkKind::Search, "/s"),
+            (
+                "https://www.amazon.com/stores/Anker/page/ABC-123/ref=ast_bln?store_ref=bl_ast",
+                LinkKind::Store("/stores/Anker/page/ABC-123".to_string()),
+                "/stores/Anker/page/ABC-123",
+            ),
+            (
+                "https://www.amazon.com/stores/page/o/ABCDEFGHIJ",
+                LinkKind::Store("/stores/page/o/ABCDEFGHIJ".to_string()),
+                "/stores/page/o/ABCDEFGHIJ",
+            ),
+            (
+                "https://www.amazon.de/hz/wishlist/ls/3abc1def2ghi/ref=nav_wishlist_lists_1",
+                LinkKind::Wishlist("3ABC1DEF2GHI".to_string()),
+                "/hz/wishlist/ls/3ABC1DEF2GHI",
+            ),
+            (
+                "https://www.amazon.de/gp/registry/wishlist/3ABC1DEF2GHI/ref=cm_wl_list_o_0",
+                LinkKind::Wishlist("3ABC1DEF2GHI".to_string()),
+                "/hz/wishlist/ls/3ABC1DEF2GHI",
+            ),
+            (
+                "https://www.amazon.com/ideas/amzn1.account.XYZ/ABCDEF/ref=idea_share",
+                LinkKind::IdeaList("/ideas/amzn1.account.XYZ/ABCDEF".to_string()),
+                "/ideas/amzn1.account.XYZ/ABCDEF",
+            ),
+            ("https://www.amazon.de/deals?ref_=nav_cs_gb", LinkKind::Deals, "/deals"),
+            ("https://www.amazon.de/gp/goldbox/ref=nav_cs_gb", LinkKind::Deals, "/deals"),
+        ] {
+            let (found, _) = classify_amazon_url(url).unwrap_or_else(|| panic!("{url}"));
+            assert_eq!(found, kind, "{url}");
+            assert_eq!(found.clean_path(), path, "{url}");
+        }
+    }
+
+    #[test]
+    fn products_keep_their_classification() {
+        let (kind, _) = classify_amazon_url("https://www.amazon.de/Lamp/dp/B08N5WRWNW/ref=sr_1_1").unwrap();
+        assert_eq!(kind, LinkKind::Product("B08N5WRWNW".to_string()));
+        assert_eq!(kind.clean_path(), "/dp/B08N5WRWNW/");
+    }
+
+    #[test]
+    fn untaggable_pages_are_not_classified() {
+        for url in [
+            // Search without keywords or category
+            "https://www.amazon.de/s",
+            "https://www.amazon.de/s?ref=nb_sb_noss",
+            "https://www.amazon.de/gp/cart/view.html",
+            "https://www.amazon.de/",
+            "https://www.example.com/s?k=lamp",
+        ] {
+            assert!(classify_amazon_url(url).is_none(), "{url}");
+        }
+    }
+
+    #[test]
+    fn ref_segments_and_trailing_slashes_are_stripped() {
+        assert_eq!(strip_ref_segments("/stores/Anker/ref=ast_bln/page/ABC/"), "/stores/Anker/page/ABC");
+        assert_eq!(strip_ref_segments("/ideas/list//ref=x"), "/ideas/list");
+        assert_eq!(strip_ref_segments("/ref=x"), "");
+    }
+}
@@ -0,0 +1,191 @@
+// src/providers/ebay.rs
+// eBay Partner Network: item and search links on the eBay sites EPN supports.
+
+use regex::Regex;
+use url::Url;
+use std::sync::LazyLock;
+use super::{AffiliateProvider, ParsedLink};
+use super::super::config;
+
+/// An eBay site supported by the eBay Partner Network
+pub struct Site {
+    /// Domain suffix after "ebay.", also used as region key, e.g. "de" or "co.uk"
+    pub tld: &'static str,
+    pub flag: &'static str,
+    pub name: &'static str,
+    /// EPN rotation ID of the site (`mkrid`)
+    pub rotation_id: &'static str,
+    /// eBay site ID (`siteid`)
+    pub site_id: &'static str,
+}
+
+pub static SITES: &[Site] = &[
+    Site { tld: "com", flag: "🇺🇸", name: "United States", rotation_id: "711-53200-19255-0", site_id: "0" },
+    Site { tld: "ca", flag: "🇨🇦", name: "Canada", rotation_id: "706-53473-19255-0", site_id: "2" },
+    Site { tld: "co.uk", flag: "🇬🇧", name: "United Kingdom", rotation_id: "710-53481-19255-0", site_id: "3" },
+    Site { tld: "de", flag: "🇩🇪", name: "Germany", rotation_id: "707-53477-19255-0", site_id: "77" },
+    Site { tld: "fr", flag: "🇫🇷", name: "France", rotation_id: "709-53476-19255-0", site_id: "71" },
+    Site { tld: "it", flag: "🇮🇹", name: "Italy", rotation_id: "724-53478-19255-0", site_id: "101" },
+    Site { tld: "es", flag: "🇪🇸", name: "Spain", rotation_id: "1185-53479-19255-0", site_id: "186" },
+    Site { tld: "at", flag: "🇦🇹", name: "Austria", rotation_id: "5221-53469-19255-0", site_id: "16" },
+    Site { tld: "ch", flag: "🇨🇭", name: "Switzerland", rotation_id: "5222-53480-19255-0", site_id: "193" },
+    Site { tld: "nl", flag: "🇳🇱", name: "Netherlands", rotation_id: "1346-53482-19255-0", site_id: "146" },
+    Site { tld: "be", flag: "🇧🇪", name: "Belgium", rotation_id: "1553-53471-19255-0", site_id: "123" },
+    Site { tld: "ie", flag: "🇮🇪", name: "Ireland", rotation_id: "5282-53468-19255-0", site_id: "205" },
+    Site { tld: "pl", flag: "🇵🇱", name: "Poland", rotation_id: "4908-226936-19255-0", site_id: "212" },
+    Site { tld: "com.au", flag: "🇦🇺", name: "Australia", rotation_id: "705-53470-19255-0", site_id: "15" },
+];
+
+/// Share-link hosts that redirect to an eBay site
+pub static SHORT_LINK_HOSTS: &[&str] = &["ebay.us"];
+
+/// Find the eBay site for a host like "www.ebay.de" or "m.ebay.co.uk"
+pub fn site_from_host(host: &str) -> Option<&'static Site> {
+    let host = host.to_lowercase();
+    SITES.iter().find(|site| {
+        let domain = format!("ebay.{}", site.tld);
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+pub fn find_site(tld: &str) -> Option<&'static Site> {
+    SITES.iter().find(|site| site.tld == tld)
+}
+
+/// Item pages: `/itm/{item}` and `/itm/{title-slug}/{item}`; item numbers are 9 to 15 digits
+static ITEM_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^/itm/(?:[^/]+/)?(\d{9,15})(?:[/?#]|$)").unwrap()
+});
+
+/// Search keywords and category filter of `/sch/` result pages
+const SEARCH_PARAMS: &[&str] = &["_nkw", "_sacat"];
+
+/// eBay Partner Network; one campaign ID covers every site
+pub struct Ebay;
+
+impl AffiliateProvider for Ebay {
+    fn id(&self) -> &'static str {
+        "ebay"
+    }
+
+    fn name(&self) -> &'static str {
+        "eBay"
+    }
+
+    fn bare_link_pattern(&self) -> &'static str {
+        r"(?:www\.)?ebay\.(?:com\.au|co\.uk|com|ca|de|fr|it|es|at|ch|nl|be|ie|pl)/|ebay\.us/"
+    }
+
+    fn handles_host(&self, host: &str) -> bool {
+        site_from_host(host).is_some() || self.is_short_link_host(host)
+    }
+
+    fn is_short_link_host(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        SHORT_LINK_HOSTS.contains(&host)
+    }
+
+    fn parse(&self, url: &Url) -> Option<ParsedLink> {
+        let site = site_from_host(url.host_str()?)?;
+        let path = url.path();
+
+        if let Some(cap) = ITEM_PATH_RE.captures(path) {
+            return Some(ParsedLink {
+                region: site.tld.to_string(),
+                label: "button-ebay-item",
+                base_url: format!("https://www.ebay.{}/itm/{}", site.tld, &cap[1]),
+                intrinsic_params: &[],
+                item_id: Some(cap[1].to_string()),
+            });
+        }
+
+        let is_search = path.to_lowercase().starts_with("/sch/")
+            && url.query_pairs().any(|(key, _)| key == "_nkw");
+        if is_search {
+            return Some(ParsedLink {
+                region: site.tld.to_string(),
+                label: "button-ebay-search",
+                base_url: format!("https://www.ebay.{}/sch/i.html", site.tld),
+                intrinsic_params: SEARCH_PARAMS,
+                item_id: None,
+            });
+        }
+        None
+    }
+
+    /// Only the campaign ID belongs to a publisher; eBay's own share links carry the rest without one
+    fn attribution_params(&self) -> &'static [&'static str] {
+        &["campid"]
+    }
+
+    fn strip_only_params(&self) -> &'static [&'static str] {
+        &["mkevt", "mkcid", "mkrid", "customid"]
+    }
+
+    /// EPN smart-link parameters, see the EPN link generator
+    fn tracking_params(&self, region: &str, tag: &str) -> Vec<(String, String)> {
+        let Some(site) = find_site(region) else {
+            return vec![("campid".to_string(), tag.to_string())];
+        };
+        [
+            ("mkcid", "1"),
+            ("mkrid", site.rotation_id),
+            ("siteid", site.site_id),
+            ("campid", tag),
+            ("toolid", "10001"),
+            ("mkevt", "1"),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+    }
+
+    fn config_region(&self, _region: &str) -> String {
+        "all".to_string()
+    }
+
+    fn default_tag(&self, _region: &str) -> String {
+        config::default_ebay_campaign_id()
+    }
+
+    fn region_label(&self, region: &str) -> String {
+        match find_site(region) {
+            Some(site) => format!("{} **{}** (ebay.{})", site.flag, site.name, site.tld),
+            None => format!("**ebay.{}**", region),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::utils;
+
+    /// An item link as copied from eBay's share button
+    const SHARE_URL: &str = "https://www.ebay.de/itm/123456789012?mkcid=16&mkevt=1&mkrid=707-127634-2357-0&ssspo=abc&sssrc=2047675&ssuid=xyz&widget_ver=artemis&media=COPY";
+
+    #[test]
+    fn share_links_carry_no_attribution() {
+        let attribution = Ebay.detect_attribution(&Url::parse(SHARE_URL).unwrap());
+        assert!(!attribution.is_affiliate(), "{attribution:?}");
+    }
+
+    #[test]
+    fn campaign_ids_are_attribution() {
+        let url = Url::parse("https://www.ebay.de/itm/123456789012?mkcid=1&mkrid=707-53477-19255-0&campid=5338999999&toolid=10001&mkevt=1").unwrap();
+        let attribution = Ebay.detect_attribution(&url);
+        assert!(attribution.is_affiliate());
+        assert_eq!(attribution.tag.as_deref(), Some("5338999999"));
+    }
+
+    #[test]
+    fn share_parameters_are_replaced_by_ours() {
+        let parsed = Ebay.parse(&Url::parse(SHARE_URL).unwrap()).unwrap();
+        let keep = vec!["mkevt".to_string(), "customid".to_string()];
+        assert_eq!(
+            utils::build_clean_url(&Ebay, &parsed, "5338000000", &format!("{}&customid=abc", SHARE_URL), &keep),
+            "https://www.ebay.de/itm/123456789012?mkcid=1&mkrid=707-53477-19255-0&siteid=77&campid=5338000000&toolid=10001&mkevt=1"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// src/providers/mod.rs
+// Affiliate networks the bot can clean and tag links for. Amazon Associates is the first
+// implementation; every provider plugs into the same detect → resolve → clean → tag pipeline.
+
+use url::Url;
+
+pub mod amazon;
+pub mod ebay;
+
+/// A resolved link a provider knows how to tag
+pub struct ParsedLink {
+    /// Provider-specific region, e.g. the Amazon marketplace TLD ("de", "co.uk") or eBay site
+    pub region: String,
+    /// Catalog message of the label for the link button in chat
+    pub label: &'static str,
+    /// Cleaned URL without any query parameters
+    pub base_url: String,
+    /// Query parameters that belong to the page itself (e.g. search keywords) and are always kept
+    pub intrinsic_params: &'static [&'static str],
+    /// Product identifier within the region (ASIN, eBay item number); `None` for searches, stores, ...
+    pub item_id: Option<String>,
+}
+
+/// Affiliate attribution already present on a link
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribution {
+    /// The network's main tracking ID (`tag=` for Amazon, `campid=` for eBay)
+    pub tag: Option<String>,
+    /// Further affiliate parameters, e.g. `linkCode`, `ascsubtag` or `mkevt`
+    pub markers: Vec<(String, String)>,
+    /// Navigation traces that are reported but not treated as affiliate tags on their own,
+    /// e.g. Amazon `/ref=...` path segments
+    pub hints: Vec<(String, String)>,
+}
+
+impl Attribution {
+    /// Whether the link carries affiliate attribution (tracking ID or other affiliate parameters)
+    pub fn is_affiliate(&self) -> bool {
+        self.tag.is_some() || !self.markers.is_empty()
+    }
+
+    /// Short human readable list, e.g. "tag=someone-21, linkCode=ll1"
+    pub fn summary(&self) -> String {
+        self.markers.iter()
+            .chain(self.hints.iter())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One affiliate network
+pub trait AffiliateProvider: Send + Sync {
+    /// Stable identifier stored in the database, e.g. "amazon"
+    fn id(&self) -> &'static str;
+
+    /// Display name, e.g. "Amazon"
+    fn name(&self) -> &'static str;
+
+    /// Regex fragment for links written without a protocol: the host plus the start of the path,
+    /// e.g. `amzn\.to/`. It is embedded in a case-insensitive regex.
+    fn bare_link_pattern(&self) -> &'static str;
+
+    /// Whether a host belongs to this network, including its short-link hosts
+    fn handles_host(&self, host: &str) -> bool;
+
+    /// Whether a host only redirects and must be resolved before parsing
+    fn is_short_link_host(&self, host: &str) -> bool;
+
+    /// Parse a resolved URL; None for pages that cannot be tagged
+    fn parse(&self, url: &Url) -> Option<ParsedLink>;
+
+    /// Query parameters carrying attribution; the first one is the main tracking ID
+    fn attribution_params(&self) -> &'static [&'static str];
+
+    /// Tracking parameters that are stripped like attribution but don't mark a link as tagged
+    /// by someone else, e.g. the `mkevt` every eBay share link carries
+    fn strip_only_params(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Navigation traces reported alongside attribution (see `Attribution::hints`)
+    fn attribution_hints(&self, _url: &Url) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Query parameters that attach our tracking ID to a link in this region
+    fn tracking_params(&self, region: &str, tag: &str) -> Vec<(String, String)>;
+
+    /// Region key used for `guild_affiliates`; networks with one ID for every region override this
+    fn config_region(&self, region: &str) -> String {
+        region.to_string()
+    }
+
+    /// Developer fallback tracking ID from the environment
+    fn default_tag(&self, region: &str) -> String;
+
+    /// Human readable region label for stats, e.g. "🇩🇪 Germany (amazon.de)"
+    fn region_label(&self, region: &str) -> String;
+
+    /// Detect existing attribution on a resolved URL
+    fn detect_attribution(&self, url: &Url) -> Attribution {
+        let mut attribution = Attribution::default();
+        for (i, name) in self.attribution_params().iter().enumerate() {
+            let value = url.query_pairs()
+                .find(|(key, value)| key.eq_ignore_ascii_case(name) && !value.trim().is_empty())
+                .map(|(_, value)| value.into_owned());
+            if let Some(value) = value {
+                if i == 0 {
+                    attribution.tag = Some(value.clone());
+                }
+                attribution.markers.push((name.to_string(), value));
+            }
+        }
+        attribution.hints = self.attribution_hints(url);
+        attribution
+    }
+}
+
+/// All supported networks, in detection order
+pub static PROVIDERS: &[&dyn AffiliateProvider] = &[&amazon::Amazon, &ebay::Ebay];
+
+/// Find a provider by its database identifier
+pub fn find(id: &str) -> Option<&'static dyn AffiliateProvider> {
+    PROVIDERS.iter().copied().find(|p| p.id() == id)
+}
+
+/// Find the provider responsible for a URL
+pub fn for_url(url: &Url) -> Option<&'static dyn AffiliateProvider> {
+    let host = url.host_str()?;
+    PROVIDERS.iter().copied().find(|p| p.handles_host(host))
+}
+
+/// Whether a host belongs to any supported network
+pub fn is_known_host(host: &str) -> bool {
+    PROVIDERS.iter().any(|p| p.handles_host(host))
+}
@@ -0,0 +1,138 @@
+// src/ratelimit.rs
+// Token-bucket rate limits for link processing per user, channel and guild,
+// so link spam can't trigger a flood of resolves, stats entries and replies.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use serenity::all::{ChannelId, GuildId, UserId};
+use super::{config, i18n};
+
+/// Buckets are dropped once this many exist and they have refilled completely
+const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    User(UserId),
+    Channel(ChannelId),
+    Guild(GuildId),
+}
+
+/// Up to `capacity` links at once, refilling completely within `period`
+struct Limit {
+    capacity: u32,
+    period: Duration,
+}
+
+impl Limit {
+    fn from_env(key: &str, default: (u32, u64)) -> Option<Self> {
+        config::rate_limit(key, default).map(|(capacity, period)| Limit { capacity, period })
+    }
+
+    /// Tokens refilled per second
+    fn rate(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+}
+
+struct Limits {
+    user: Option<Limit>,
+    channel: Option<Limit>,
+    guild: Option<Limit>,
+}
+
+static LIMITS: LazyLock<Limits> = LazyLock::new(|| Limits {
+    user: Limit::from_env("RATE_LIMIT_USER", (10, 60)),
+    channel: Limit::from_env("RATE_LIMIT_CHANNEL", (30, 60)),
+    guild: Limit::from_env("RATE_LIMIT_GUILD", (100, 60)),
+});
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// The cooldown message for this bucket was sent already
+    notified: bool,
+}
+
+static BUCKETS: LazyLock<Mutex<HashMap<Key, Bucket>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A request over the limit
+#[derive(Debug, Clone, Copy)]
+pub struct Limited {
+    /// Until enough tokens are back
+    pub retry_after: Duration,
+    /// First time over the limit since the last allowed request; only then a cooldown message is sent
+    pub notify: bool,
+}
+
+/// Take `cost` tokens (one per link) from the user's, channel's and guild's buckets.
+/// Nothing is taken unless every bucket has enough.
+pub fn acquire(user: UserId, channel: ChannelId, guild: Option<GuildId>, cost: u32) -> Result<(), Limited> {
+    let mut checks = Vec::new();
+    if let Some(limit) = &LIMITS.user {
+        checks.push((Key::User(user), limit));
+    }
+    if let Some(limit) = &LIMITS.channel {
+        checks.push((Key::Channel(channel), limit));
+    }
+    if let (Some(limit), Some(guild)) = (&LIMITS.guild, guild) {
+        checks.push((Key::Guild(guild), limit));
+    }
+
+    let now = Instant::now();
+    let mut buckets = BUCKETS.lock().unwrap();
+    if buckets.len() > MAX_BUCKETS {
+        prune(&mut buckets, now);
+    }
+
+    for (key, limit) in &checks {
+        let bucket = buckets.entry(*key).or_insert(Bucket {
+            tokens: limit.capacity as f64,
+            updated: now,
+            notified: false,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.rate()).min(limit.capacity as f64);
+        bucket.updated = now;
+
+        // A message with more links than the capacity needs a full bucket
+        let needed = cost.min(limit.capacity) as f64;
+        if bucket.tokens < needed {
+            let notify = !bucket.notified;
+            bucket.notified = true;
+            return Err(Limited {
+                retry_after: Duration::from_secs_f64((needed - bucket.tokens) / limit.rate()),
+                notify,
+            });
+        }
+    }
+
+    for (key, limit) in &checks {
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.tokens -= cost.min(limit.capacity) as f64;
+            bucket.notified = false;
+        }
+    }
+    Ok(())
+}
+
+/// Drop buckets that have refilled completely; they behave like new ones
+fn prune(buckets: &mut HashMap<Key, Bucket>, now: Instant) {
+    buckets.retain(|key, bucket| {
+        let limit = match key {
+            Key::User(_) => &LIMITS.user,
+            Key::Channel(_) => &LIMITS.channel,
+            Key::Guild(_) => &LIMITS.guild,
+        };
+        limit.as_ref().is_some_and(|limit| {
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens + elapsed * limit.rate() < limit.capacity as f64
+        })
+    });
+}
+
+/// Friendly cooldown message, e.g. for the first message over the limit
+pub fn cooldown_message(limited: &Limited, locale: &str) -> String {
+    let secs = (limited.retry_after.as_secs_f64().ceil() as u64).max(1);
+    i18n::format(locale, "cooldown", &[("seconds", secs.into())])
+}
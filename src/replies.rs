@@ -0,0 +1,74 @@
+// src/replies.rs
+// Remembers which bot reply answers which member message, so edits of the message
+// update that reply instead of adding another one.
+
+use rusqlite::params;
+use serenity::all::{Message, MessageId};
+use super::{db, rules, utils};
+
+/// How long replies are remembered; edits of older messages get a new reply
+pub const RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// The bot's reply to a member message. Only a hash of the message text is kept, plus its links.
+pub struct Reply {
+    pub reply_id: MessageId,
+    /// SHA-256 of the member message's content when the reply was last sent or updated
+    pub content_hash: String,
+    /// Affiliate links in the member message at that time
+    pub links: Vec<String>,
+}
+
+/// Hash of a message's content, to tell edits that change it from other updates
+pub fn content_hash(content: &str) -> String {
+    utils::sha256_hex(content.as_bytes())
+}
+
+/// Look up the bot's reply to a message
+pub fn find(message_id: MessageId) -> Option<Reply> {
+    db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT reply_message_id, content_hash, links FROM bot_replies WHERE message_id = ?",
+            params![message_id.get().to_string()],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?)),
+        )
+    })
+    .ok()
+    .and_then(|(reply_id, content_hash, links)| {
+        let reply_id = reply_id.parse::<u64>().ok().filter(|id| *id != 0)?;
+        Some(Reply {
+            reply_id: MessageId::new(reply_id),
+            content_hash,
+            links: links.lines().map(str::to_string).collect(),
+        })
+    })
+}
+
+/// Remember the reply to a message together with the hash and links of the content it answers
+pub fn record(msg: &Message, reply_id: MessageId) {
+    let rules = msg.guild_id
+        .map(|id| rules::for_guild(&id.get().to_string()))
+        .unwrap_or_default();
+    let links = utils::extract_affiliate_urls(&msg.content, &rules);
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO bot_replies (message_id, channel_id, reply_message_id, content_hash, links, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                msg.id.get().to_string(),
+                msg.channel_id.get().to_string(),
+                reply_id.get().to_string(),
+                content_hash(&msg.content),
+                links.join("\n"),
+                chrono::Utc::now().timestamp()
+            ],
+        )
+    });
+}
+
+pub fn forget(message_id: MessageId) {
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "DELETE FROM bot_replies WHERE message_id = ?",
+            params![message_id.get().to_string()],
+        )
+    });
+}
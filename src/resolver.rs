@@ -0,0 +1,264 @@
+// src/resolver.rs
+// Follows affiliate short-link redirects (amzn.to, ebay.us, ...) without letting user input reach arbitrary hosts.
+// Results are cached in SQLite and requests share one pooled HTTP client.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::LazyLock;
+use std::time::Duration;
+use reqwest::{Client, Method, StatusCode, header::LOCATION};
+use rusqlite::params;
+use url::Url;
+use super::{config, db, providers};
+
+/// Maximum number of redirect hops before giving up
+const MAX_REDIRECTS: usize = 10;
+/// Timeout for establishing a single connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Timeout for a single request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for the whole redirect chain
+const TOTAL_TIMEOUT: Duration = Duration::from_secs(10);
+/// Response bodies are never needed; at most this much of a GET redirect body is drained
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Why a link could not be resolved
+#[derive(Debug)]
+pub enum ResolveError {
+    /// Not an absolute http(s) URL
+    InvalidUrl,
+    /// Host does not belong to a supported affiliate network
+    HostNotAllowed(String),
+    /// Host resolves to a private, loopback or otherwise internal address
+    PrivateAddress(String),
+    /// Host name could not be resolved
+    Unreachable(String),
+    TooManyRedirects,
+    TimedOut,
+    BodyTooLarge,
+    Http(reqwest::Error),
+    /// An earlier attempt failed recently and the failure is still cached
+    Cached(String),
+}
+
+impl ResolveError {
+    /// Whether the link was refused by policy rather than failing on the network
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, Self::InvalidUrl | Self::HostNotAllowed(_) | Self::PrivateAddress(_))
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl => write!(f, "not a valid http(s) URL"),
+            Self::HostNotAllowed(host) => write!(f, "host {} is not a supported shop host", host),
+            Self::PrivateAddress(host) => write!(f, "host {} resolves to a private address", host),
+            Self::Unreachable(host) => write!(f, "host {} could not be resolved", host),
+            Self::TooManyRedirects => write!(f, "more than {} redirects", MAX_REDIRECTS),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::BodyTooLarge => write!(f, "response body larger than {} bytes", MAX_BODY_BYTES),
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::Cached(reason) => write!(f, "recently failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+impl From<reqwest::Error> for ResolveError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() { Self::TimedOut } else { Self::Http(e) }
+    }
+}
+
+/// Shared client so connections to the shops are pooled across resolutions
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .connect_timeout(CONNECT_TIMEOUT)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(8)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// Check whether an address is loopback, private, link-local or otherwise not publicly routable
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                // Carrier-grade NAT 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b))
+        },
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_internal(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local fc00::/7 and link-local fe80::/10
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        },
+    }
+}
+
+/// Check a URL before requesting it: http(s), a supported shop host, and only public addresses
+async fn check_target(url: &Url) -> Result<(), ResolveError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ResolveError::InvalidUrl);
+    }
+    let host = url.host_str().ok_or(ResolveError::InvalidUrl)?;
+    if !providers::is_known_host(host) {
+        return Err(ResolveError::HostNotAllowed(host.to_string()));
+    }
+
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<_> = tokio::net::lookup_host((host, port)).await
+        .map_err(|_| ResolveError::Unreachable(host.to_string()))?
+        .collect();
+    if addrs.is_empty() || addrs.iter().any(|addr| is_internal(addr.ip())) {
+        return Err(ResolveError::PrivateAddress(host.to_string()));
+    }
+    Ok(())
+}
+
+/// Send a single request without following redirects.
+/// Redirect bodies of GET requests are drained up to `MAX_BODY_BYTES`; final pages are never read.
+async fn request(client: &Client, method: Method, url: &Url) -> Result<reqwest::Response, ResolveError> {
+    let mut resp = client.request(method.clone(), url.clone())
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?;
+    if method == Method::GET && resp.status().is_redirection() {
+        if resp.content_length().is_some_and(|len| len > MAX_BODY_BYTES as u64) {
+            return Err(ResolveError::BodyTooLarge);
+        }
+        let mut read = 0;
+        while let Some(chunk) = resp.chunk().await? {
+            read += chunk.len();
+            if read > MAX_BODY_BYTES {
+                return Err(ResolveError::BodyTooLarge);
+            }
+        }
+    }
+    Ok(resp)
+}
+
+/// Follow the redirect chain of an affiliate short link and return the final URL.
+/// Every hop must stay on a host of a supported network and resolve to a public address.
+/// Each hop is tried with HEAD first and falls back to GET if HEAD is not answered properly.
+/// Successful results are cached for `RESOLVE_CACHE_TTL_SECS`, network failures for `RESOLVE_NEGATIVE_TTL_SECS`.
+pub async fn resolve_url(input: &str) -> Result<String, ResolveError> {
+    if let Some(cached) = cached(input) {
+        return cached;
+    }
+
+    let result = match tokio::time::timeout(TOTAL_TIMEOUT, follow_redirects(input)).await {
+        Ok(result) => result,
+        Err(_) => Err(ResolveError::TimedOut),
+    };
+
+    // Refused links are cheap to check again and never reach the network, so only cache real outcomes
+    match &result {
+        Ok(resolved) => store(input, Some(resolved), None),
+        Err(e) if !e.is_blocked() => store(input, None, Some(&e.to_string())),
+        Err(_) => {},
+    }
+    result
+}
+
+/// Look up a cache entry that is still within its TTL
+fn cached(input: &str) -> Option<Result<String, ResolveError>> {
+    let (resolved, error, resolved_at) = db::with_connection(|conn| {
+        conn.query_row(
+            "SELECT resolved_url, error, resolved_at FROM resolved_links WHERE short_url = ?",
+            params![input],
+            |r| Ok((r.get::<_, Option<String>>(0)?, r.get::<_, Option<String>>(1)?, r.get::<_, i64>(2)?)),
+        )
+    }).ok()?;
+
+    let age = chrono::Utc::now().timestamp() - resolved_at;
+    match resolved {
+        Some(url) if age < config::resolve_cache_ttl().as_secs() as i64 => Some(Ok(url)),
+        None if age < config::resolve_negative_ttl().as_secs() as i64 => {
+            Some(Err(ResolveError::Cached(error.unwrap_or_default())))
+        },
+        _ => None,
+    }
+}
+
+/// Remember the outcome of a resolution
+fn store(input: &str, resolved: Option<&str>, error: Option<&str>) {
+    let _ = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO resolved_links (short_url, resolved_url, error, resolved_at) VALUES (?, ?, ?, ?)",
+            params![input, resolved, error, chrono::Utc::now().timestamp()],
+        )
+    });
+}
+
+async fn follow_redirects(input: &str) -> Result<String, ResolveError> {
+    let client = &*CLIENT;
+
+    let mut current = Url::parse(input).map_err(|_| ResolveError::InvalidUrl)?;
+    for _ in 0..=MAX_REDIRECTS {
+        check_target(&current).await?;
+
+        let mut resp = request(client, Method::HEAD, &current).await;
+        let head_failed = match &resp {
+            Ok(r) => !r.status().is_redirection() && !r.status().is_success(),
+            Err(e) => !e.is_blocked() && !matches!(e, ResolveError::TimedOut),
+        };
+        if head_failed {
+            resp = request(client, Method::GET, &current).await;
+        }
+        let resp = resp?;
+
+        let status = resp.status();
+        if !status.is_redirection() || status == StatusCode::NOT_MODIFIED {
+            return Ok(current.to_string());
+        }
+        let location = resp.headers().get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(ResolveError::InvalidUrl)?;
+        current = current.join(location).map_err(|_| ResolveError::InvalidUrl)?;
+    }
+    Err(ResolveError::TooManyRedirects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_link_hosts_pass_the_host_check() {
+        for host in ["amzn.to", "amzn.eu", "amzn.asia", "amzn.com", "a.co"] {
+            assert!(providers::is_known_host(host), "{host}");
+        }
+    }
+
+    #[tokio::test]
+    async fn lookalike_hosts_are_refused_before_any_request() {
+        for url in ["https://amzn.com.evil.example/d/abc", "https://a.co.evil.example/d/abc", "https://amzn.eu.org/d/abc"] {
+            let result = check_target(&Url::parse(url).unwrap()).await;
+            assert!(matches!(result, Err(ResolveError::HostNotAllowed(_))), "{url}: {result:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn non_http_schemes_are_refused() {
+        let result = check_target(&Url::parse("ftp://amzn.to/abc").unwrap()).await;
+        assert!(matches!(result, Err(ResolveError::InvalidUrl)), "{result:?}");
+    }
+}
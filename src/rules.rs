@@ -0,0 +1,308 @@
+// src/rules.rs
+// Guild-defined rewrite rules for shops without a built-in affiliate provider:
+// strip tracking parameters from matching links and add the guild's own (e.g. `ref=ourserver`).
+
+use std::fmt;
+use rusqlite::params;
+use url::Url;
+use super::{db, providers};
+
+/// Network id used in `link_stats` for links rewritten by a guild rule
+pub const NETWORK_ID: &str = "custom";
+/// Maximum number of rules per guild
+pub const MAX_RULES: usize = 25;
+const MAX_PARAMS: usize = 20;
+
+/// A rewrite rule of one guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRule {
+    pub id: i64,
+    /// Domain the rule applies to, including its subdomains, e.g. "aliexpress.com"
+    pub host: String,
+    /// Lowercase parameter names to remove; `*` removes every parameter
+    pub strip: Vec<String>,
+    /// Parameters appended to every matching link
+    pub add: Vec<(String, String)>,
+}
+
+/// Why a rule was rejected on save
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// Not a plain domain name like "shop.example"
+    InvalidHost(String),
+    /// The host belongs to a built-in network, which has its own cleaning logic
+    BuiltInHost(String),
+    /// Parameter names may only use letters, digits, `_`, `-`, `.` and `[]`
+    InvalidParam(String),
+    /// Rule would neither strip nor add anything
+    Empty,
+    TooManyParams,
+    TooManyRules,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHost(host) => write!(f, "`{}` is not a valid domain like `shop.example`", host),
+            Self::BuiltInHost(host) => write!(f, "`{}` is handled by the built-in Amazon/eBay logic", host),
+            Self::InvalidParam(param) => write!(f, "`{}` is not a valid query parameter", param),
+            Self::Empty => write!(f, "the rule neither strips nor adds any parameter"),
+            Self::TooManyParams => write!(f, "at most {} parameters to strip and {} to add", MAX_PARAMS, MAX_PARAMS),
+            Self::TooManyRules => write!(f, "a server can have at most {} rules", MAX_RULES),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+fn is_valid_param(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 50
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "_-.[]".contains(c))
+}
+
+impl RewriteRule {
+    /// Validate and normalize user input: a host pattern (a leading `*.` is optional),
+    /// a comma/space separated list of parameters to strip and a query string to add (`ref=ourserver&src=discord`).
+    pub fn parse(host: &str, strip: &str, add: &str) -> Result<Self, RuleError> {
+        let host = host.trim().to_lowercase();
+        let host = host.strip_prefix("*.").unwrap_or(&host).trim_end_matches('.').to_string();
+        let labels: Vec<&str> = host.split('.').collect();
+        let valid_host = labels.len() >= 2
+            && labels.iter().all(|l| {
+                !l.is_empty() && l.len() <= 63 && !l.starts_with('-') && !l.ends_with('-')
+                    && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+            // Top-level domains are never numeric, which also rules out IP addresses
+            && !labels.last().is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit()));
+        if !valid_host {
+            return Err(RuleError::InvalidHost(host));
+        }
+        // Covers the host itself and any built-in host below it ("amazon.de", "co.uk", ...)
+        let clashes = providers::is_known_host(&host)
+            || providers::is_known_host(&format!("amazon.{}", host))
+            || providers::is_known_host(&format!("ebay.{}", host));
+        if clashes {
+            return Err(RuleError::BuiltInHost(host));
+        }
+
+        let mut strip_params: Vec<String> = strip
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if let Some(bad) = strip_params.iter().find(|p| p.as_str() != "*" && !is_valid_param(p)) {
+            return Err(RuleError::InvalidParam(bad.clone()));
+        }
+        strip_params.sort();
+        strip_params.dedup();
+
+        let add = add.trim().trim_start_matches('?');
+        let add_params: Vec<(String, String)> = url::form_urlencoded::parse(add.as_bytes())
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        if let Some((bad, _)) = add_params.iter().find(|(key, _)| !is_valid_param(key)) {
+            return Err(RuleError::InvalidParam(bad.clone()));
+        }
+
+        if strip_params.is_empty() && add_params.is_empty() {
+            return Err(RuleError::Empty);
+        }
+        if strip_params.len() > MAX_PARAMS || add_params.len() > MAX_PARAMS {
+            return Err(RuleError::TooManyParams);
+        }
+        Ok(RewriteRule { id: 0, host, strip: strip_params, add: add_params })
+    }
+
+    /// Whether the rule applies to a host (the domain itself or a subdomain)
+    pub fn matches_host(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        host == self.host || host.ends_with(&format!(".{}", self.host))
+    }
+
+    /// Rewrite a URL: drop stripped parameters (and earlier values of added ones), then append the added parameters
+    pub fn apply(&self, url: &Url) -> Url {
+        let strip_all = self.strip.iter().any(|p| p == "*");
+        let kept: Vec<(String, String)> = url.query_pairs()
+            .filter(|(key, _)| {
+                let key = key.to_lowercase();
+                !strip_all
+                    && !self.strip.contains(&key)
+                    && !self.add.iter().any(|(added, _)| added.eq_ignore_ascii_case(&key))
+            })
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+
+        let mut rewritten = url.clone();
+        rewritten.set_query(None);
+        if !kept.is_empty() || !self.add.is_empty() {
+            rewritten.query_pairs_mut()
+                .extend_pairs(kept)
+                .extend_pairs(self.add.iter().cloned());
+        }
+        rewritten
+    }
+
+    /// Stripped parameters for display, e.g. "aff_id, utm_source"
+    pub fn strip_display(&self) -> String {
+        if self.strip.is_empty() { "—".to_string() } else { self.strip.join(", ") }
+    }
+
+    /// Added parameters as a query string, e.g. "ref=ourserver"
+    pub fn add_query(&self) -> String {
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.add.iter())
+            .finish()
+    }
+
+    pub fn add_display(&self) -> String {
+        if self.add.is_empty() { "—".to_string() } else { self.add_query() }
+    }
+}
+
+/// Find the rule for a URL; the most specific host wins
+pub fn matching<'a>(rules: &'a [RewriteRule], url_str: &str) -> Option<&'a RewriteRule> {
+    let url = Url::parse(url_str).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?;
+    rules.iter()
+        .filter(|rule| rule.matches_host(host))
+        .max_by_key(|rule| rule.host.len())
+}
+
+/// Load all rules of a guild, oldest first
+pub fn for_guild(guild_id: &str) -> Vec<RewriteRule> {
+    db::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, host_pattern, strip_params, add_params FROM guild_rewrite_rules WHERE guild_id = ? ORDER BY id"
+        )?;
+        let rows = stmt.query_map(params![guild_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap_or_default()
+    .into_iter()
+    // Stored rules were validated on save; re-parsing keeps a single representation
+    .filter_map(|(id, host, strip, add)| {
+        RewriteRule::parse(&host, &strip, &add).ok().map(|rule| RewriteRule { id, ..rule })
+    })
+    .collect()
+}
+
+/// Save a validated rule for a guild and return its id. Adding a rule for an existing host replaces it.
+pub fn save(guild_id: &str, rule: &RewriteRule) -> Result<i64, Box<dyn std::error::Error>> {
+    let existing = for_guild(guild_id);
+    let replaces = existing.iter().any(|r| r.host == rule.host);
+    if !replaces && existing.len() >= MAX_RULES {
+        return Err(Box::new(RuleError::TooManyRules));
+    }
+    let id = db::with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO guild_rewrite_rules (guild_id, host_pattern, strip_params, add_params) VALUES (?, ?, ?, ?)
+             ON CONFLICT(guild_id, host_pattern) DO UPDATE SET strip_params = excluded.strip_params, add_params = excluded.add_params",
+            params![guild_id, rule.host, rule.strip.join(","), rule.add_query()],
+        )?;
+        conn.query_row(
+            "SELECT id FROM guild_rewrite_rules WHERE guild_id = ? AND host_pattern = ?",
+            params![guild_id, rule.host],
+            |r| r.get(0),
+        )
+    })?;
+    Ok(id)
+}
+
+/// Delete a rule of a guild; returns whether it existed
+pub fn remove(guild_id: &str, id: i64) -> rusqlite::Result<bool> {
+    db::with_connection(|conn| {
+        conn.execute(
+            "DELETE FROM guild_rewrite_rules WHERE guild_id = ? AND id = ?",
+            params![guild_id, id],
+        )
+    })
+    .map(|deleted| deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(host: &str, strip: &str, add: &str) -> RewriteRule {
+        RewriteRule::parse(host, strip, add).unwrap()
+    }
+
+    #[test]
+    fn rules_are_normalized() {
+        let parsed = rule(" *.Shop.Example. ", "UTM_source, aff_id aff_id", "?ref=ourserver&src=discord");
+        assert_eq!(parsed.host, "shop.example");
+        assert_eq!(parsed.strip, vec!["aff_id", "utm_source"]);
+        assert_eq!(parsed.add, vec![("ref".to_string(), "ourserver".to_string()), ("src".to_string(), "discord".to_string())]);
+    }
+
+    #[test]
+    fn invalid_hosts_are_rejected() {
+        for host in ["example", "", "shop..example", "-shop.example", "shop-.example", "shop_1.example", "https://shop.example", "shop.example/path", "192.168.0.1"] {
+            assert!(matches!(RewriteRule::parse(host, "aff_id", ""), Err(RuleError::InvalidHost(_))), "{host}");
+        }
+    }
+
+    #[test]
+    fn built_in_hosts_are_rejected() {
+        // The built-in hosts, their subdomains and domains above them
+        for (host, normalized) in [
+            ("amazon.de", "amazon.de"),
+            ("www.amazon.co.uk", "www.amazon.co.uk"),
+            ("*.amazon.com", "amazon.com"),
+            ("co.uk", "co.uk"),
+            ("amzn.to", "amzn.to"),
+            ("ebay.de", "ebay.de"),
+        ] {
+            assert_eq!(RewriteRule::parse(host, "aff_id", ""), Err(RuleError::BuiltInHost(normalized.to_string())), "{host}");
+        }
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        assert_eq!(RewriteRule::parse("shop.example", "aff id=1", ""), Err(RuleError::InvalidParam("id=1".to_string())));
+        assert_eq!(RewriteRule::parse("shop.example", "", "r%20f=x"), Err(RuleError::InvalidParam("r f".to_string())));
+        assert_eq!(RewriteRule::parse("shop.example", "", "=x"), Err(RuleError::InvalidParam(String::new())));
+        assert_eq!(RewriteRule::parse("shop.example", " , ", ""), Err(RuleError::Empty));
+        let many = (0..=MAX_PARAMS).map(|i| format!("p{i}")).collect::<Vec<_>>().join(",");
+        assert_eq!(RewriteRule::parse("shop.example", &many, ""), Err(RuleError::TooManyParams));
+        // `*` is only valid as a parameter to strip
+        assert!(RewriteRule::parse("shop.example", "*", "").is_ok());
+        assert!(matches!(RewriteRule::parse("shop.example", "", "*=x"), Err(RuleError::InvalidParam(_))));
+    }
+
+    #[test]
+    fn the_most_specific_host_wins() {
+        let rules = [
+            RewriteRule { id: 1, ..rule("shop.example", "a", "") },
+            RewriteRule { id: 2, ..rule("de.shop.example", "b", "") },
+            RewriteRule { id: 3, ..rule("other.example", "c", "") },
+        ];
+        let id = |url: &str| matching(&rules, url).map(|rule| rule.id);
+        assert_eq!(id("https://www.de.shop.example/item"), Some(2));
+        assert_eq!(id("https://de.shop.example/item"), Some(2));
+        assert_eq!(id("https://fr.shop.example/item"), Some(1));
+        assert_eq!(id("https://shop.example/item"), Some(1));
+        // Neither a lookalike nor another scheme
+        assert_eq!(id("https://myshop.example/item"), None);
+        assert_eq!(id("ftp://shop.example/item"), None);
+    }
+
+    #[test]
+    fn added_params_replace_existing_ones() {
+        let rule = rule("shop.example", "aff_id, utm_source", "ref=ourserver");
+        let url = Url::parse("https://shop.example/item?id=7&REF=someone&aff_id=1&utm_source=x&ref=other").unwrap();
+        assert_eq!(rule.apply(&url).as_str(), "https://shop.example/item?id=7&ref=ourserver");
+
+        let strip_all = RewriteRule { strip: vec!["*".to_string()], ..rule.clone() };
+        assert_eq!(strip_all.apply(&url).as_str(), "https://shop.example/item?ref=ourserver");
+        let strip_only = RewriteRule { add: Vec::new(), strip: vec!["*".to_string()], ..rule };
+        assert_eq!(strip_only.apply(&url).as_str(), "https://shop.example/item");
+    }
+}
@@ -1,167 +1,866 @@
 // src/utils.rs
 use regex::Regex;
-use reqwest::Client;
 use url::Url;
 use rusqlite::params;
+use std::sync::LazyLock;
+use std::time::Duration;
+use futures::stream::{self, StreamExt};
+use super::providers::{self, AffiliateProvider, Attribution, PROVIDERS};
+use super::marketplace::{self, Marketplace};
+use super::{i18n, resolver};
+use super::rules::{self, RewriteRule};
 
-pub async fn resolve_url(input: &str) -> reqwest::Result<String> {
-    let client = Client::builder().redirect(reqwest::redirect::Policy::limited(10)).build()?;
-    let resp = client.get(input).send().await?;
-    Ok(resp.url().to_string())
+/// Links written with a protocol. Whitespace, `<`, `>`, backticks and `|` never belong to a
+/// link in Discord markdown; other wrapping syntax is trimmed afterwards.
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)https?://[^\s<>`|]+").unwrap()
+});
+
+/// Affiliate links written without a protocol, built from each provider's `bare_link_pattern`
+/// ("amazon.de/...", "amzn.to/...", "ebay.de/itm/..."). Share-link hosts need a path to count as a link.
+/// The link must not follow a word character, dot or slash; matches inside links written with a
+/// protocol (`?k=amazon.de/...`) are skipped by `find_affiliate_links`.
+static BARE_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let hosts = PROVIDERS.iter()
+        .map(|p| format!("(?:{})", p.bare_link_pattern()))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(r"(?i)(?:^|[^\w./@-])((?:{})[^\s<>`|]*)", hosts)).unwrap()
+});
+
+/// Check whether a URL belongs to a supported affiliate network (including short-link hosts)
+pub fn is_affiliate_url(url_str: &str) -> bool {
+    Url::parse(url_str).ok()
+        .and_then(|url| url.host_str().map(providers::is_known_host))
+        .unwrap_or(false)
 }
 
-/// Parse an Amazon URL and return (ASIN, region)
-/// region will be everything after "amazon.", e.g. "de", "co.uk", "com"
-pub fn parse_amazon_url(url_str: &str) -> Option<(String, String)> {
-    let url = Url::parse(url_str).ok()?;
-    let host = url.host_str()?; // e.g. "www.amazon.co.uk" or "smile.amazon.com"
-    // strip "www." or any subdomain before "amazon."
-    let domain = host
-        .trim_start_matches("www.")
-        .trim_start_matches("smile.")
-        .trim_start_matches("smile-redirect.")
-        ; // now "amazon.de", "amazon.co.uk", "amazon.com"...
-    // take what comes after "amazon."
-    let region = domain
-        .strip_prefix("amazon.")
-        .unwrap_or("de") // default to "de" if something odd happens
-        .to_string();
-
-    // capture the ASIN from a "/dp/ASIN/" path segment
-    let re = Regex::new(r"/dp/([A-Z0-9]+)/?").unwrap();
-    re.captures(url.path())
-      .and_then(|cap| cap.get(1))
-      .map(|m| (m.as_str().to_string(), region))
-}
-
-/// Extract all Amazon URLs from a message content
-pub fn extract_amazon_urls(content: &str) -> Vec<String> {
-    let mut urls = Vec::new();
-    
+/// Check whether a URL is a short link that has to be resolved before parsing
+pub fn is_short_link(url_str: &str) -> bool {
+    Url::parse(url_str).ok()
+        .and_then(|url| {
+            let host = url.host_str()?;
+            providers::for_url(&url).map(|p| p.is_short_link_host(host))
+        })
+        .unwrap_or(false)
+}
+
+/// An affiliate link found in a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundLink {
+    /// The link with an `https://` prefix added if it was written without a protocol
+    pub url: String,
+    /// Byte range of the link as written in the message, without markdown wrapping
+    pub range: std::ops::Range<usize>,
+}
+
+/// Byte ranges of code spans and fenced code blocks; links inside them are posted on purpose
+fn code_ranges(content: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = content.as_bytes();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        // A run of N backticks opens a span that is closed by the next run of exactly N backticks
+        let start = i;
+        while i < bytes.len() && bytes[i] == b'`' {
+            i += 1;
+        }
+        let fence = &content[start..i];
+        let mut search = i;
+        let mut close = None;
+        while let Some(offset) = content[search..].find(fence) {
+            let pos = search + offset;
+            let end = pos + fence.len();
+            if bytes.get(end) != Some(&b'`') && (pos == 0 || bytes[pos - 1] != b'`') {
+                close = Some(end);
+                break;
+            }
+            search = end;
+            while search < bytes.len() && bytes[search] == b'`' {
+                search += 1;
+            }
+        }
+        match close {
+            Some(end) => {
+                ranges.push(start..end);
+                i = end;
+            },
+            // Unclosed fenced blocks run to the end of the message; a lone backtick is just text
+            None if fence.len() >= 3 => {
+                ranges.push(start..bytes.len());
+                break;
+            },
+            None => {},
+        }
+    }
+    ranges
+}
+
+/// Trim markdown wrapping and trailing punctuation from a link match, returning the new length.
+/// `before` is the text preceding the link, used to detect symmetric wrappers like `_link_`.
+fn trim_link_end(link: &str, before: &str) -> usize {
+    let mut end = link.len();
+    loop {
+        let current = &link[..end];
+        let Some(last) = current.chars().last() else {
+            break;
+        };
+        let strip = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | '*' | '~' | ']' | '}' => true,
+            // Masked links `[text](url)` and parenthesised links; keep balanced parentheses
+            ')' => current.matches('(').count() < current.matches(')').count(),
+            // Underscores are valid in URLs, only strip them as the closing half of `_link_`
+            '_' => before.ends_with('_'),
+            _ => false,
+        };
+        if !strip {
+            break;
+        }
+        end -= last.len_utf8();
+    }
+    end
+}
+
+/// Find all affiliate links in a message, in order of appearance: links of the built-in networks
+/// and links matching one of the guild's rewrite `rules` (those need an explicit `http(s)://`).
+/// Links inside code spans and fenced code blocks are ignored; markdown wrapping
+/// (`[text](url)`, `<url>`, `||url||`, `**url**`) and trailing punctuation are stripped.
+pub fn find_affiliate_links(content: &str, rules: &[RewriteRule]) -> Vec<FoundLink> {
+    let code = code_ranges(content);
+    let in_code = |pos: usize| code.iter().any(|range| range.contains(&pos));
+
+    let mut links = Vec::new();
+    let mut push = |start: usize, raw: &str, with_protocol: bool| {
+        if in_code(start) {
+            return;
+        }
+        // The text of a masked link `[text](url)` ends at `](`
+        let raw = raw.split("](").next().unwrap_or(raw);
+        let len = trim_link_end(raw, &content[..start]);
+        let text = &raw[..len];
+        let url = if with_protocol { text.to_string() } else { format!("https://{}", text) };
+        if is_affiliate_url(&url) || rules::matching(rules, &url).is_some() {
+            links.push(FoundLink { url, range: start..start + len });
+        }
+    };
+
     // First find URLs with protocols (http:// or https://)
-    // Using a more robust regex that handles complex URLs
-    let url_regex = Regex::new(r"https?://[^\s]+").unwrap();
-    
-    for url_match in url_regex.find_iter(content) {
-        let url = url_match.as_str();
-        if url.contains("amazon.") || url.contains("amzn.to") {
-            urls.push(url.to_string());
+    let mut url_ranges = Vec::new();
+    for m in URL_RE.find_iter(content) {
+        url_ranges.push(m.range());
+        // A masked link whose text is a URL too, `[url](url)`, is matched as a whole; both URLs are links
+        let mut start = m.start();
+        for part in m.as_str().split("](") {
+            if URL_RE.find(part).is_some_and(|url| url.start() == 0) {
+                push(start, part, true);
+            }
+            start += part.len() + "](".len();
         }
     }
-    
-    // Then find URLs without protocols (amazon.* or amzn.to)
-    let amazon_regex = Regex::new(r"(?:^|[\s])((?:amazon\.[a-z.]+|amzn\.to)[^\s]*)").unwrap();
-    
-    for cap in amazon_regex.captures_iter(content) {
-        if let Some(url_match) = cap.get(1) {
-            let url = url_match.as_str();
-            // Add https:// prefix if not already present
-            let full_url = if url.starts_with("http") {
-                url.to_string()
-            } else {
-                format!("https://{}", url)
-            };
-            
-            // Avoid duplicates from the first regex
-            if !urls.contains(&full_url) {
-                urls.push(full_url);
+    // Then find URLs without protocols (amazon.*, amzn.to/, ebay.de/, ...), except inside any of the
+    // URLs above, affiliate or not
+    for cap in BARE_LINK_RE.captures_iter(content) {
+        if let Some(m) = cap.get(1) {
+            if !url_ranges.iter().any(|range| range.contains(&m.start())) {
+                push(m.start(), m.as_str(), false);
             }
         }
     }
-    
+
+    links.sort_by_key(|link| link.range.start);
+    // Links must not overlap, the first one wins
+    links.dedup_by(|link, previous| link.range.start < previous.range.end);
+    links
+}
+
+/// Extract all distinct affiliate URLs from a message content, in order of appearance
+pub fn extract_affiliate_urls(content: &str, rules: &[RewriteRule]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for link in find_affiliate_links(content, rules) {
+        if !urls.contains(&link.url) {
+            urls.push(link.url);
+        }
+    }
     urls
 }
 
-/// Check if a message contains only Amazon links (and whitespace)
-pub fn is_amazon_link_only(content: &str) -> bool {
-    let trimmed = content.trim();
-    
-    // Check if the message contains Amazon links
-    if !trimmed.contains("amazon.") && !trimmed.contains("amzn.to") {
+/// Check if a message contains only affiliate links (plus whitespace and markdown wrapping)
+pub fn is_affiliate_link_only(content: &str, rules: &[RewriteRule]) -> bool {
+    let links = find_affiliate_links(content, rules);
+    if links.is_empty() {
         return false;
     }
-    
-    // Remove all URLs (with and without protocols) and check if anything meaningful remains
-    let mut remaining = trimmed.to_string();
-    
-    // Remove URLs with protocols
-    let url_regex = Regex::new(r"https?://[^\s]+").unwrap();
-    remaining = url_regex.replace_all(&remaining, "").to_string();
-    
-    // Remove URLs without protocols (amazon.* or amzn.to)
-    let amazon_regex = Regex::new(r"(?:^|[\s])((?:amazon\.[a-z.]+|amzn\.to)[^\s]*)").unwrap();
-    remaining = amazon_regex.replace_all(&remaining, "").to_string();
-    
-    // If after removing URLs there's only whitespace, it's a link-only message
-    remaining.trim().is_empty()
+
+    // Remove the links and check if anything meaningful remains besides markdown syntax
+    let mut remaining = String::new();
+    let mut last = 0;
+    for link in &links {
+        if link.range.start < last {
+            continue;
+        }
+        remaining.push_str(&content[last..link.range.start]);
+        last = link.range.end;
+    }
+    remaining.push_str(&content[last..]);
+
+    // Other (unsupported) URLs and code spans count as content
+    remaining
+        .chars()
+        .all(|c| c.is_whitespace() || "<>|*_~()[].,;:!?".contains(c))
 }
 
-/// Process an Amazon URL and return (clean_url, footer_text)
-/// Similar to the amazon command logic but as a utility function
-pub async fn process_amazon_url(url: &str, guild_id: Option<String>) -> Option<(String, String)> {
-    // Resolve redirects
-    let resolved = resolve_url(url).await.unwrap_or_else(|_| url.to_string());
-    
-    // Parse ASIN and region
-    if let Some((asin, region)) = parse_amazon_url(&resolved) {
-        let is_dm = guild_id.is_none();
-        let guild_id_str = guild_id.unwrap_or_else(|| "DM".to_string());
-        
-        // Determine tracking tag and footer based on context (same logic as amazon command)
-        let (tag, footer_template) = if is_dm {
-            // Use default developer tags and signature for DMs
-            let default_tag = super::config::default_tracking_tag(&region);
-            let default_signature = super::config::default_signature();
-            (default_tag, default_signature)
-        } else {
-            // Try to get guild-specific settings, fallback to defaults
-            let default_template = "Using this link you support our server!".to_string();
-            
-            let (guild_tag, guild_footer) = super::db::with_connection(|conn| {
-                let tag: String = conn.query_row(
-                    "SELECT tracking_tag FROM guild_affiliates WHERE guild_id = ? AND region = ?",
-                    params![guild_id_str, region],
-                    |r| r.get(0),
-                ).unwrap_or_else(|_| String::new());
-                
-                let footer: String = conn.query_row(
-                        "SELECT footer_text FROM guild_settings WHERE guild_id = ?",
-                        params![guild_id_str],
-                        |r| r.get(0),
-                    )
-                    .unwrap_or_else(|_| default_template.clone());
-                Ok((tag, footer))
+/// Replace every found link in `content` by its cleaned version; links without one stay as written.
+/// `found` must come from `find_affiliate_links` on the same content.
+pub fn rewrite_content(content: &str, found: &[FoundLink], links: &[ProcessedLink]) -> String {
+    let mut rewritten = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in found {
+        if link.range.start < last {
+            continue;
+        }
+        let Some(processed) = links.iter().find(|p| p.source_url == link.url) else {
+            continue;
+        };
+        rewritten.push_str(&content[last..link.range.start]);
+        rewritten.push_str(&processed.clean_url);
+        last = link.range.end;
+    }
+    rewritten.push_str(&content[last..]);
+    rewritten
+}
+
+/// Load the query parameters a guild keeps in cleaned links (lowercase names)
+pub fn kept_params(guild_id: &str) -> Vec<String> {
+    super::db::with_connection(|conn| {
+        let mut stmt = conn.prepare("SELECT param FROM guild_kept_params WHERE guild_id = ? ORDER BY param")?;
+        let params = stmt.query_map(params![guild_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(params)
+    })
+    .unwrap_or_default()
+}
+
+/// Whether a channel or category is processed or skipped by the message handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelFilter {
+    Allow,
+    Deny,
+}
+
+impl ChannelFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Allow, Self::Deny].into_iter().find(|f| f.as_str() == value)
+    }
+}
+
+/// Load a guild's channel and category filters as (channel id, filter)
+pub fn channel_filters(guild_id: &str) -> Vec<(String, ChannelFilter)> {
+    super::db::with_connection(|conn| {
+        let mut stmt = conn.prepare("SELECT channel_id, mode FROM guild_channel_filters WHERE guild_id = ?")?;
+        let rows = stmt.query_map(params![guild_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|(channel, mode)| ChannelFilter::parse(&mode).map(|filter| (channel, filter)))
+    .collect()
+}
+
+/// Decide whether links in a channel are processed. `chain` lists the channel, its parent
+/// channel (for threads) and its category, most specific first; the most specific filter wins.
+/// Without a matching filter a channel is processed unless the guild has an allowlist.
+pub fn is_channel_processed(guild_id: &str, chain: &[String]) -> bool {
+    let filters = channel_filters(guild_id);
+    let decided = chain.iter()
+        .find_map(|id| filters.iter().find(|(channel, _)| channel == id).map(|(_, filter)| *filter));
+    match decided {
+        Some(filter) => filter == ChannelFilter::Allow,
+        None => !filters.iter().any(|(_, filter)| *filter == ChannelFilter::Allow),
+    }
+}
+
+/// Roles that change whether a member's messages are processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleFilter {
+    /// Members with this role are never processed (moderators, trusted members)
+    Exempt,
+    /// If any of these exist, only members with one of them are processed
+    Only,
+}
+
+impl RoleFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exempt => "exempt",
+            Self::Only => "only",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Exempt, Self::Only].into_iter().find(|f| f.as_str() == value)
+    }
+}
+
+/// Load a guild's role filters as (role id, filter)
+pub fn role_filters(guild_id: &str) -> Vec<(String, RoleFilter)> {
+    super::db::with_connection(|conn| {
+        let mut stmt = conn.prepare("SELECT role_id, mode FROM guild_role_filters WHERE guild_id = ?")?;
+        let rows = stmt.query_map(params![guild_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|(role, mode)| RoleFilter::parse(&mode).map(|filter| (role, filter)))
+    .collect()
+}
+
+/// Decide whether a member's links are processed, given the role ids on their message.
+/// Exempt roles always win; "only" roles, if any are configured, must be present.
+pub fn is_member_processed(guild_id: &str, roles: &[String]) -> bool {
+    let filters = role_filters(guild_id);
+    let has = |filter: RoleFilter| filters.iter().any(|(role, f)| *f == filter && roles.contains(role));
+    if has(RoleFilter::Exempt) {
+        return false;
+    }
+    let restricted = filters.iter().any(|(_, f)| *f == RoleFilter::Only);
+    !restricted || has(RoleFilter::Only)
+}
+
+/// Build the cleaned, tagged URL for a parsed link, e.g.
+/// `https://amazon.{region}/dp/{asin}/?{kept}&tag={tag}` for Amazon products.
+/// Only the page's own parameters (search keywords) and those in `keep` are copied over from
+/// `source`; attribution parameters of the network are always replaced by ours.
+pub fn build_clean_url(provider: &dyn AffiliateProvider, parsed: &providers::ParsedLink, tag: &str, source: &str, keep: &[String]) -> String {
+    let mut clean = Url::parse(&parsed.base_url)
+        .expect("providers always build absolute base URLs");
+
+    let tracking = provider.tracking_params(&parsed.region, tag);
+    let is_attribution = |key: &str| {
+        provider.attribution_params().iter()
+            .chain(provider.strip_only_params())
+            .any(|p| p.eq_ignore_ascii_case(key))
+            || tracking.iter().any(|(p, _)| p.eq_ignore_ascii_case(key))
+    };
+    let kept: Vec<(String, String)> = Url::parse(source).ok()
+        .map(|url| url.query_pairs()
+            .filter(|(key, _)| {
+                let key = key.to_lowercase();
+                !is_attribution(&key) && (parsed.intrinsic_params.contains(&key.as_str()) || keep.contains(&key))
             })
-            .unwrap_or((String::new(), default_template.clone()));
-            
-            // If no guild tag configured, use default developer tag
-            if guild_tag.is_empty() {
-                let default_tag = super::config::default_tracking_tag(&region);
-                let default_signature = super::config::default_signature();
-                (default_tag, default_signature)
-            } else {
-                (guild_tag, guild_footer)
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect())
+        .unwrap_or_default();
+
+    clean.query_pairs_mut()
+        .extend_pairs(kept)
+        .extend_pairs(tracking);
+    clean.to_string()
+}
+
+/// How the message handler treats links that already carry someone else's affiliate tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignTagPolicy {
+    /// Replace the tag with the guild's own (default)
+    Replace,
+    /// Leave the link untouched
+    Keep,
+    /// Delete the message and tell the author
+    Reject,
+    /// Replace the tag and report the message to the mod channel
+    Flag,
+}
+
+impl ForeignTagPolicy {
+    pub const ALL: [ForeignTagPolicy; 4] = [Self::Replace, Self::Keep, Self::Reject, Self::Flag];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Replace => "replace",
+            Self::Keep => "keep",
+            Self::Reject => "reject",
+            Self::Flag => "flag",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+
+    /// Policy configured for a guild; DMs and unconfigured guilds use `Replace`
+    pub fn for_guild(guild_id: Option<&str>) -> Self {
+        guild_id
+            .and_then(|id| super::db::guild_setting(id, "foreign_tag_policy"))
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or(Self::Replace)
+    }
+}
+
+/// How the message handler responds to messages with affiliate links
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// Delete link-only messages and hint at `/amazon`; mixed messages get a button reply (default)
+    DeleteAndHint,
+    /// Leave the message alone and reply with buttons
+    ButtonReply,
+    /// Leave the message alone and reply with the cleaned links as text
+    TextReply,
+    /// Suppress the original message's embeds and reply with the cleaned links as text
+    SuppressEmbedsAndReply,
+    /// Delete the message and repost it under the author's name through a webhook, with cleaned links
+    WebhookRepost,
+    /// Ignore links
+    Off,
+}
+
+impl LinkMode {
+    pub const ALL: [LinkMode; 6] = [
+        Self::DeleteAndHint,
+        Self::ButtonReply,
+        Self::TextReply,
+        Self::SuppressEmbedsAndReply,
+        Self::WebhookRepost,
+        Self::Off,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeleteAndHint => "delete-and-hint",
+            Self::ButtonReply => "button-reply",
+            Self::TextReply => "text-reply",
+            Self::SuppressEmbedsAndReply => "suppress-embeds-and-reply",
+            Self::WebhookRepost => "webhook-repost",
+            Self::Off => "off",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == value)
+    }
+
+    /// Mode for a channel: the most specific override in `chain` (the channel, its parent channel for
+    /// threads and its category, as for `is_channel_processed`), then the guild setting, then `DeleteAndHint`
+    pub fn for_channel(guild_id: &str, chain: &[String]) -> Self {
+        chain.iter()
+            .find_map(|channel_id| super::db::channel_setting(channel_id, "link_mode"))
+            .or_else(|| super::db::guild_setting(guild_id, "link_mode"))
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or(Self::DeleteAndHint)
+    }
+}
+
+/// Determine tracking tag and footer template for a provider region.
+/// Guilds use their own tag and footer; DMs and guilds without a tag for this region
+/// fall back to the developer default tag and signature.
+pub fn tag_and_footer(guild_id: Option<&str>, provider: &dyn AffiliateProvider, region: &str) -> (String, String) {
+    let Some(guild_id) = guild_id else {
+        // Use default developer tags and signature for DMs
+        return (provider.default_tag(region), super::config::default_signature());
+    };
+
+    // Try to get guild-specific settings, fallback to defaults
+    let guild_tag = super::db::with_connection(|conn| {
+        Ok(conn.query_row(
+            "SELECT tracking_tag FROM guild_affiliates WHERE guild_id = ? AND provider = ? AND region = ?",
+            params![guild_id, provider.id(), provider.config_region(region)],
+            |r| r.get(0),
+        ).unwrap_or_else(|_| String::new()))
+    })
+    .unwrap_or_default();
+    
+    // If no guild tag configured, use default developer tag
+    if guild_tag.is_empty() {
+        (provider.default_tag(region), super::config::default_signature())
+    } else {
+        (guild_tag, guild_footer(guild_id))
+    }
+}
+
+/// Footer template configured for a guild, or the default one in the guild's language
+pub fn guild_footer(guild_id: &str) -> String {
+    let default_template = i18n::text(i18n::resolve(Some(guild_id), ""), "footer-default");
+    super::db::with_connection(|conn| {
+        Ok(conn.query_row(
+            "SELECT footer_text FROM guild_settings WHERE guild_id = ? AND footer_text != ''",
+            params![guild_id],
+            |r| r.get(0),
+        ).unwrap_or_else(|_| default_template.clone()))
+    })
+    .unwrap_or(default_template)
+}
+
+/// How long hints stay in the channel unless the guild configured a lifetime
+pub const DEFAULT_HINT_LIFETIME: Duration = Duration::from_secs(10);
+/// Allowed range for a guild's hint lifetime, in seconds
+pub const HINT_LIFETIME_RANGE: std::ops::RangeInclusive<u64> = 3..=3600;
+/// Maximum length of a guild's hint text
+pub const MAX_HINT_LENGTH: usize = 500;
+
+/// How the hint refers to the `/amazon` command
+const HINT_COMMAND: &str = "`/amazon <link>`";
+
+/// Hint for link-only messages: the guild's own text with `{{sender}}`, `{{command}}` and `{{lifetime}}`
+/// filled in, or the built-in hint in the guild's language (`discord_locale` is its Discord locale)
+pub fn guild_hint(guild_id: &str, discord_locale: &str, sender: &str) -> String {
+    let lifetime = hint_lifetime(guild_id).as_secs();
+    match super::db::guild_setting(guild_id, "hint_text") {
+        Some(template) => template
+            .replace("{{sender}}", sender)
+            .replace("{{command}}", HINT_COMMAND)
+            .replace("{{lifetime}}", &lifetime.to_string()),
+        None => i18n::format(i18n::resolve_hint(Some(guild_id), discord_locale), "hint-default", &[
+            ("sender", sender.into()),
+            ("command", HINT_COMMAND.into()),
+            ("lifetime", lifetime.into()),
+        ]),
+    }
+}
+
+/// How long hints and notices stay in the guild's channels before they are deleted
+pub fn hint_lifetime(guild_id: &str) -> Duration {
+    super::db::guild_setting(guild_id, "hint_lifetime_secs")
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| HINT_LIFETIME_RANGE.contains(secs))
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_HINT_LIFETIME)
+}
+
+/// Record a generated link in `link_stats`
+pub fn log_link(guild_id: &str, provider: &str, region: &str) {
+    let _ = super::db::with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO link_stats (guild_id, provider, region) VALUES (?, ?, ?)",
+            params![guild_id, provider, region],
+        )
+    });
+}
+
+/// Lowercase hex SHA-256 digest, for data that is compared but must not be stored as-is
+pub fn sha256_hex(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data).as_ref().iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A cleaned and tagged affiliate link, ready to be posted
+pub struct ProcessedLink {
+    /// The link as found in the message (before resolving short links)
+    pub source_url: String,
+    pub clean_url: String,
+    pub footer_template: String,
+    /// Catalog message of the label for the link button in chat
+    pub label: &'static str,
+    /// Provider id, or `rules::NETWORK_ID` for links rewritten by a guild rule
+    pub network: &'static str,
+    /// Provider region; the link's host for rewritten links
+    pub region: String,
+    /// Product identifier within the region (ASIN, eBay item number), if the link points to a product
+    pub item_id: Option<String>,
+    /// Affiliate attribution on the original link that is not our own tag
+    pub foreign_attribution: Option<Attribution>,
+}
+
+/// Why a resolved link could not be cleaned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkError {
+    /// No provider handles the link, or the page cannot be tagged
+    Unsupported,
+    /// Neither the guild nor the developer configured a tracking ID for this region
+    NoTag,
+}
+
+/// Clean and tag an already resolved link with the tag configured for the guild (or the developer default).
+/// Does not log usage; callers record the link with `log_link` once it is actually posted.
+pub fn clean_link(resolved: &str, guild_id: Option<&str>) -> Result<ProcessedLink, LinkError> {
+    let url = Url::parse(resolved).map_err(|_| LinkError::Unsupported)?;
+    let provider = providers::for_url(&url).ok_or(LinkError::Unsupported)?;
+    let parsed = provider.parse(&url).ok_or(LinkError::Unsupported)?;
+
+    // Determine tracking tag and footer based on context
+    let (tag, footer_template) = tag_and_footer(guild_id, provider, &parsed.region);
+    if tag.is_empty() {
+        return Err(LinkError::NoTag);
+    }
+
+    // Detect attribution left by someone else (our own tag doesn't count)
+    let attribution = provider.detect_attribution(&url);
+    let is_own_tag = attribution.tag.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(&tag));
+    let foreign_attribution = (attribution.is_affiliate() && !is_own_tag).then_some(attribution);
+
+    // Build cleaned URL, keeping only the guild's allowed query parameters
+    let keep = kept_params(guild_id.unwrap_or("DM"));
+    let clean_url = build_clean_url(provider, &parsed, &tag, resolved, &keep);
+
+    Ok(ProcessedLink {
+        source_url: resolved.to_string(),
+        clean_url,
+        footer_template,
+        label: parsed.label,
+        network: provider.id(),
+        region: parsed.region,
+        item_id: parsed.item_id,
+        foreign_attribution,
+    })
+}
+
+/// The same Amazon product on another marketplace, tagged with the tag for that marketplace.
+/// Only product links can be converted; an ASIN is the same on every marketplace.
+pub fn convert_link(link: &ProcessedLink, tld: &str, guild_id: Option<&str>) -> Result<ProcessedLink, LinkError> {
+    let asin = link.item_id.as_deref()
+        .filter(|_| link.network == providers::amazon::Amazon.id())
+        .ok_or(LinkError::Unsupported)?;
+    let marketplace = marketplace::find(tld).filter(|m| m.associates).ok_or(LinkError::Unsupported)?;
+    let converted = clean_link(&format!("https://www.{}/dp/{}", marketplace.domain(), asin), guild_id)?;
+    Ok(ProcessedLink { source_url: link.source_url.clone(), ..converted })
+}
+
+/// Maximum number of "also offer" marketplaces; together with the posted one they fill a row of buttons
+pub const MAX_ALSO_OFFER: usize = 4;
+
+/// Parse a comma/space separated list of marketplace TLDs ("com, co.uk"); returns the first unknown entry on error
+pub fn parse_marketplaces(input: &str) -> Result<Vec<&'static Marketplace>, String> {
+    let mut marketplaces: Vec<&'static Marketplace> = Vec::new();
+    for tld in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|tld| !tld.is_empty()) {
+        let tld = tld.trim_start_matches("amazon.");
+        let marketplace = marketplace::find(tld)
+            .filter(|m| m.associates)
+            .ok_or_else(|| tld.to_string())?;
+        if !marketplaces.iter().any(|m| m.tld == marketplace.tld) {
+            marketplaces.push(marketplace);
+        }
+    }
+    Ok(marketplaces)
+}
+
+/// Marketplaces a guild offers in addition to the posted one, in configured order
+pub fn also_offer(guild_id: &str) -> Vec<&'static Marketplace> {
+    super::db::guild_setting(guild_id, "also_offer")
+        .and_then(|value| parse_marketplaces(&value).ok())
+        .unwrap_or_default()
+}
+
+/// Process an affiliate link found in a message: resolve short links, then clean and tag it.
+/// Short links that fail to resolve are tried as written.
+pub async fn process_link(url: &str, guild_id: Option<String>) -> Option<ProcessedLink> {
+    // Resolve redirects for short links only; full URLs can be parsed directly
+    let resolved = if is_short_link(url) {
+        resolver::resolve_url(url).await.unwrap_or_else(|_| url.to_string())
+    } else {
+        url.to_string()
+    };
+    let mut link = clean_link(&resolved, guild_id.as_deref()).ok()?;
+    link.source_url = url.to_string();
+    Some(link)
+}
+
+/// Apply a guild rewrite rule to a link of a shop without a built-in provider
+pub fn rewrite_link(url_str: &str, rule: &RewriteRule, guild_id: &str) -> Option<ProcessedLink> {
+    let url = Url::parse(url_str).ok()?;
+    Some(ProcessedLink {
+        source_url: url_str.to_string(),
+        clean_url: rule.apply(&url).to_string(),
+        footer_template: guild_footer(guild_id),
+        label: "button-open-link",
+        network: rules::NETWORK_ID,
+        region: url.host_str()?.trim_start_matches("www.").to_lowercase(),
+        item_id: None,
+        foreign_attribution: None,
+    })
+}
+
+/// Maximum number of links of one message that are resolved at the same time
+const MAX_CONCURRENT_LINKS: usize = 4;
+/// Time budget for a single link; a slow link is skipped instead of holding up the others
+const LINK_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Process several links concurrently (at most `MAX_CONCURRENT_LINKS` at once).
+/// Built-in networks take precedence; other links are rewritten by the matching guild rule.
+/// Results keep the order of `urls`; links that fail or exceed `LINK_TIMEOUT` are left out.
+pub async fn process_links(urls: &[String], guild_id: Option<String>, rules: &[RewriteRule]) -> Vec<ProcessedLink> {
+    let work: Vec<(String, Option<RewriteRule>)> = urls.iter()
+        .map(|url| {
+            let rule = if is_affiliate_url(url) { None } else { rules::matching(rules, url).cloned() };
+            (url.clone(), rule)
+        })
+        .collect();
+    let results: Vec<Option<ProcessedLink>> = stream::iter(work)
+        .map(|(url, rule)| {
+            let guild_id = guild_id.clone();
+            async move {
+                if let Some(rule) = rule {
+                    return rewrite_link(&url, &rule, guild_id.as_deref().unwrap_or("DM"));
+                }
+                tokio::time::timeout(LINK_TIMEOUT, process_link(&url, guild_id))
+                    .await
+                    .ok()
+                    .flatten()
             }
+        })
+        .buffered(MAX_CONCURRENT_LINKS)
+        .collect()
+        .await;
+    results.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT_LINKS: [&str; 5] = [
+        "https://amzn.to/3xYzAbC",
+        "https://amzn.eu/d/0aBcDeF",
+        "https://amzn.asia/d/1gHiJkL",
+        "https://amzn.com/dp/B08N5WRWNW",
+        "https://a.co/d/2mNoPqR",
+    ];
+
+    #[test]
+    fn short_links_are_routed_to_the_resolver() {
+        for url in SHORT_LINKS {
+            assert!(is_affiliate_url(url), "{url}");
+            assert!(is_short_link(url), "{url}");
+        }
+        assert!(!is_short_link("https://www.amazon.de/dp/B08N5WRWNW"));
+    }
+
+    #[test]
+    fn short_links_are_extracted() {
+        for url in SHORT_LINKS {
+            let content = format!("look at this {} nice", url);
+            assert_eq!(extract_affiliate_urls(&content, &[]), vec![url.to_string()]);
+        }
+    }
+
+    #[test]
+    fn short_links_without_protocol_are_extracted() {
+        for url in SHORT_LINKS {
+            let bare = url.trim_start_matches("https://");
+            let content = format!("look at this {} nice", bare);
+            assert_eq!(extract_affiliate_urls(&content, &[]), vec![url.to_string()]);
+        }
+    }
+
+    #[test]
+    fn lookalike_hosts_are_not_extracted() {
+        for content in [
+            "https://amzn.com.evil.example/d/abc",
+            "amzn.com.evil.example/d/abc",
+            "https://a.co.evil.example/d/abc",
+            "https://evil.example/amzn.to/abc",
+        ] {
+            assert!(extract_affiliate_urls(content, &[]).is_empty(), "{content}");
+            assert!(!is_short_link(content), "{content}");
+        }
+    }
+
+    /// The links found in `content`, as written there
+    fn found(content: &str) -> Vec<&str> {
+        find_affiliate_links(content, &[]).into_iter()
+            .map(|link| &content[link.range])
+            .collect()
+    }
+
+    #[test]
+    fn masked_links_are_split_at_the_target() {
+        let content = "[https://amazon.de/dp/B08N5WRWNW](https://amazon.de/dp/B08N5WRWNW)";
+        assert_eq!(found(content), vec!["https://amazon.de/dp/B08N5WRWNW", "https://amazon.de/dp/B08N5WRWNW"]);
+        assert_eq!(extract_affiliate_urls(content, &[]), vec!["https://amazon.de/dp/B08N5WRWNW".to_string()]);
+        assert!(is_affiliate_link_only(content, &[]));
+
+        let content = "[this lamp](https://amazon.de/dp/B08N5WRWNW) is great";
+        assert_eq!(found(content), vec!["https://amazon.de/dp/B08N5WRWNW"]);
+        assert!(!is_affiliate_link_only(content, &[]));
+
+        let content = "[amazon.de/dp/B08N5WRWNW](https://www.amazon.de/dp/B08N5WRWNW?th=1)";
+        assert_eq!(found(content), vec!["amazon.de/dp/B08N5WRWNW", "https://www.amazon.de/dp/B08N5WRWNW?th=1"]);
+    }
+
+    #[test]
+    fn masked_links_are_rewritten_in_place() {
+        let content = "see [https://amazon.de/dp/B08N5WRWNW](https://amazon.de/dp/B08N5WRWNW)!";
+        let links = find_affiliate_links(content, &[]);
+        let processed = ProcessedLink {
+            source_url: "https://amazon.de/dp/B08N5WRWNW".to_string(),
+            clean_url: "https://amazon.de/dp/B08N5WRWNW/?tag=x-21".to_string(),
+            footer_template: String::new(),
+            label: "button-amazon-product",
+            network: "amazon",
+            region: "de".to_string(),
+            item_id: Some("B08N5WRWNW".to_string()),
+            foreign_attribution: None,
         };
-        
-        // If still no tag available, return None
-        if tag.is_empty() {
-            return None;
-        }
-        
-        // Log usage
-        let _ = super::db::with_connection(|conn| {
-            conn.execute(
-                "INSERT INTO link_stats (guild_id, region) VALUES (?, ?)",
-                params![guild_id_str, region],
-            )
-        });
-        
-        // Build cleaned URL
-        let clean_url = format!("https://amazon.{}/dp/{}/?tag={}", region, asin, tag);
-        
-        Some((clean_url, footer_template))
-    } else {
-        None
+        assert_eq!(
+            rewrite_content(content, &links, &[processed]),
+            "see [https://amazon.de/dp/B08N5WRWNW/?tag=x-21](https://amazon.de/dp/B08N5WRWNW/?tag=x-21)!"
+        );
+    }
+
+    #[test]
+    fn links_in_code_are_ignored() {
+        assert!(found("`https://amazon.de/dp/B08N5WRWNW`").is_empty());
+        assert!(found("``a ` https://amazon.de/dp/B08N5WRWNW``").is_empty());
+        assert!(found("```\nhttps://amazon.de/dp/B08N5WRWNW\n```").is_empty());
+        // Unclosed fences run to the end of the message, a lone backtick is just text
+        assert!(found("```\namzn.to/abc").is_empty());
+        assert_eq!(found("it's ` https://amzn.to/abc"), vec!["https://amzn.to/abc"]);
+        assert_eq!(found("`code` https://amzn.to/abc `more`"), vec!["https://amzn.to/abc"]);
+    }
+
+    #[test]
+    fn wrapping_and_trailing_punctuation_are_trimmed() {
+        for (content, link) in [
+            ("https://amazon.de/dp/B08N5WRWNW.", "https://amazon.de/dp/B08N5WRWNW"),
+            ("https://amazon.de/dp/B08N5WRWNW?!", "https://amazon.de/dp/B08N5WRWNW"),
+            ("(see https://amazon.de/dp/B08N5WRWNW), ok", "https://amazon.de/dp/B08N5WRWNW"),
+            ("<https://amazon.de/dp/B08N5WRWNW>", "https://amazon.de/dp/B08N5WRWNW"),
+            ("||https://amazon.de/dp/B08N5WRWNW||", "https://amazon.de/dp/B08N5WRWNW"),
+            ("**https://amazon.de/dp/B08N5WRWNW**", "https://amazon.de/dp/B08N5WRWNW"),
+            ("~~https://amazon.de/dp/B08N5WRWNW~~", "https://amazon.de/dp/B08N5WRWNW"),
+            ("_https://amazon.de/dp/B08N5WRWNW_", "https://amazon.de/dp/B08N5WRWNW"),
+            ("\"amazon.de/dp/B08N5WRWNW\"", "amazon.de/dp/B08N5WRWNW"),
+            // Balanced parentheses and underscores belong to the link
+            ("https://amazon.de/Some-Lamp_(Black)/dp/B08N5WRWNW", "https://amazon.de/Some-Lamp_(Black)/dp/B08N5WRWNW"),
+            ("https://amazon.de/s?k=desk_lamp", "https://amazon.de/s?k=desk_lamp"),
+        ] {
+            assert_eq!(found(content), vec![link], "{content}");
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn bare_links_inside_other_urls_are_ignored() {
+        let content = "https://www.amazon.de/s?k=amazon.de/dp/B08N5WRWNW";
+        assert_eq!(found(content), vec![content]);
+        assert!(is_affiliate_link_only(content, &[]));
+        let processed = ProcessedLink {
+            source_url: content.to_string(),
+            clean_url: "https://www.amazon.de/s?k=lamp".to_string(),
+            footer_template: String::new(),
+            label: "button-amazon-search",
+            network: "amazon",
+            region: "de".to_string(),
+            item_id: None,
+            foreign_attribution: None,
+        };
+        assert_eq!(rewrite_content(content, &find_affiliate_links(content, &[]), &[processed]), "https://www.amazon.de/s?k=lamp");
+
+        let content = "look https://example.com/?u=amazon.de/dp/B08N5WRWNW";
+        assert!(found(content).is_empty());
+        assert!(!is_affiliate_link_only(content, &[]));
+    }
+}
@@ -0,0 +1,170 @@
+// src/watch.rs
+// Price watches: members watch an Amazon product and get an alert, tagged with the guild's
+// affiliate configuration, once its price falls below their target.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use rusqlite::params;
+use serenity::all::{ChannelId, CreateMessage, Mentionable, UserId};
+use serenity::http::Http;
+use super::{config, db, i18n, prices::PriceSource, utils};
+
+/// Maximum number of watches per user
+pub const MAX_WATCHES: usize = 25;
+/// Pause between two lookups of a check run; the Product Advertising API allows one request per second
+const LOOKUP_PAUSE: Duration = Duration::from_millis(1100);
+
+/// Set once the check loop runs; `ready` fires again after reconnects
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// A user's watch on a product
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watch {
+    pub id: i64,
+    pub user_id: String,
+    /// Guild whose affiliate tags are used for the alert; `None` for watches created in DMs
+    pub guild_id: Option<String>,
+    /// Amazon marketplace TLD, e.g. "de"
+    pub region: String,
+    pub asin: String,
+    /// Alert once the price is below this amount
+    pub target_price: f64,
+    /// Discord locale of the user who created the watch; the alert uses it unless the guild set a language
+    pub locale: Option<String>,
+}
+
+impl Watch {
+    pub fn product_url(&self) -> String {
+        format!("https://www.amazon.{}/dp/{}", self.region, self.asin)
+    }
+}
+
+fn query(sql: &str, param: Option<&str>) -> Vec<Watch> {
+    db::with_connection(|conn| {
+        let mut stmt = conn.prepare(sql)?;
+        let map = |row: &rusqlite::Row| {
+            Ok(Watch {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                guild_id: row.get(2)?,
+                region: row.get(3)?,
+                asin: row.get(4)?,
+                target_price: row.get(5)?,
+                locale: row.get(6)?,
+            })
+        };
+        let rows = match param {
+            Some(param) => stmt.query_map(params![param], map)?.collect::<Result<Vec<_>, _>>()?,
+            None => stmt.query_map([], map)?.collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok(rows)
+    })
+    .unwrap_or_default()
+}
+
+/// All watches of a user, oldest first
+pub fn for_user(user_id: &str) -> Vec<Watch> {
+    query(
+        "SELECT id, user_id, guild_id, region, asin, target_price, locale FROM price_watches WHERE user_id = ? ORDER BY id",
+        Some(user_id),
+    )
+}
+
+fn all() -> Vec<Watch> {
+    query("SELECT id, user_id, guild_id, region, asin, target_price, locale FROM price_watches ORDER BY id", None)
+}
+
+/// Add a watch and return its id; watching a product again updates the target
+pub fn add(user_id: &str, guild_id: Option<&str>, region: &str, asin: &str, target_price: f64, locale: &str) -> rusqlite::Result<i64> {
+    db::with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO price_watches (user_id, guild_id, region, asin, target_price, locale, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(user_id, region, asin) DO UPDATE SET
+                guild_id = excluded.guild_id, target_price = excluded.target_price, locale = excluded.locale",
+            params![user_id, guild_id, region, asin, target_price, locale, chrono::Utc::now().timestamp()],
+        )?;
+        conn.query_row(
+            "SELECT id FROM price_watches WHERE user_id = ? AND region = ? AND asin = ?",
+            params![user_id, region, asin],
+            |r| r.get(0),
+        )
+    })
+}
+
+/// Delete a watch of a user; returns whether it existed
+pub fn remove(user_id: &str, id: i64) -> rusqlite::Result<bool> {
+    db::with_connection(|conn| {
+        conn.execute("DELETE FROM price_watches WHERE user_id = ? AND id = ?", params![user_id, id])
+    })
+    .map(|deleted| deleted > 0)
+}
+
+/// Check all watches every `WATCH_INTERVAL_SECS`
+pub fn start(http: &Arc<Http>) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let http = http.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(config::watch_interval()).await;
+            check_all(&http).await;
+        }
+    });
+}
+
+async fn check_all(http: &Http) {
+    let source = PriceSource::from_config();
+    for watch in all() {
+        let price = source.current_price(watch.guild_id.as_deref(), &watch.region, &watch.asin).await;
+        if let Some(price) = price.filter(|price| price.amount < watch.target_price) {
+            // Watches end with their alert; failed alerts are tried again next time
+            if alert(http, &watch, &price.display).await {
+                let _ = remove(&watch.user_id, watch.id);
+            }
+        }
+        if source == PriceSource::ProductApi {
+            tokio::time::sleep(LOOKUP_PAUSE).await;
+        }
+    }
+}
+
+/// Send the alert to the guild's deals channel, or to the user by DM if the guild has none (or it can't be posted to)
+async fn alert(http: &Http, watch: &Watch, price: &str) -> bool {
+    let Some(user_id) = watch.user_id.parse::<u64>().ok().filter(|id| *id != 0).map(UserId::new) else {
+        return false;
+    };
+    // Tagged like any other link of the guild
+    let Ok(link) = utils::clean_link(&watch.product_url(), watch.guild_id.as_deref()) else {
+        return false;
+    };
+    let locale = i18n::resolve(watch.guild_id.as_deref(), watch.locale.as_deref().unwrap_or_default());
+    let details = i18n::format(locale, "watch-alert", &[
+        ("price", price.into()),
+        ("target", format!("{:.2}", watch.target_price).into()),
+        ("link", link.clean_url.as_str().into()),
+    ]);
+    let deals_channel = watch.guild_id.as_deref()
+        .and_then(|guild_id| db::guild_setting(guild_id, "deals_channel_id"))
+        .and_then(|id| id.parse::<u64>().ok())
+        .filter(|id| *id != 0)
+        .map(ChannelId::new);
+
+    let mut sent = false;
+    if let Some(channel_id) = deals_channel {
+        let message = CreateMessage::new().content(format!("{} {}", user_id.mention(), details));
+        // Channel deleted or no access: tell the user directly
+        sent = channel_id.send_message(http, message).await.is_ok();
+    }
+    if !sent {
+        sent = match user_id.create_dm_channel(http).await {
+            Ok(dm) => dm.send_message(http, CreateMessage::new().content(details)).await.is_ok(),
+            Err(_) => false,
+        };
+    }
+    if sent {
+        utils::log_link(watch.guild_id.as_deref().unwrap_or("DM"), link.network, &link.region);
+    }
+    sent
+}
@@ -0,0 +1,99 @@
+// src/webhook.rs
+// Reposts a member's message through a channel webhook under their name and avatar,
+// used by the `webhook-repost` link mode.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use serenity::all::{
+    Channel, ChannelId, ChannelType, CreateAllowedMentions, CreateAttachment, CreateWebhook,
+    ExecuteWebhook, Message, Webhook,
+};
+use serenity::prelude::*;
+
+/// Name of the webhook the bot creates in each channel
+const WEBHOOK_NAME: &str = "Affilify";
+/// Upload limit for webhook messages in servers without boosts
+const MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Webhooks by channel, so each repost needs a single request
+static WEBHOOKS: LazyLock<Mutex<HashMap<ChannelId, Webhook>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Find the bot's webhook in a channel, creating it if needed
+async fn channel_webhook(ctx: &Context, channel_id: ChannelId) -> Option<Webhook> {
+    if let Some(webhook) = WEBHOOKS.lock().unwrap().get(&channel_id) {
+        return Some(webhook.clone());
+    }
+
+    let bot_id = ctx.cache.current_user().id;
+    let existing = channel_id.webhooks(&ctx.http).await.ok()?
+        .into_iter()
+        .find(|webhook| webhook.token.is_some() && webhook.user.as_ref().is_some_and(|user| user.id == bot_id));
+    let webhook = match existing {
+        Some(webhook) => webhook,
+        None => channel_id.create_webhook(&ctx.http, CreateWebhook::new(WEBHOOK_NAME)).await.ok()?,
+    };
+    WEBHOOKS.lock().unwrap().insert(channel_id, webhook.clone());
+    Some(webhook)
+}
+
+/// Repost a message with new content under the author's name and avatar, keeping its attachments.
+/// Threads are posted to through their parent channel's webhook.
+/// Returns false if nothing was posted (missing Manage Webhooks permission, attachments too large, ...);
+/// the original message is left alone either way.
+pub async fn repost(ctx: &Context, msg: &Message, content: String) -> bool {
+    if content.chars().count() > 2000 {
+        return false;
+    }
+    let total_size: u64 = msg.attachments.iter().map(|a| a.size as u64).sum();
+    if total_size > MAX_UPLOAD_BYTES {
+        return false;
+    }
+
+    // Webhooks belong to the parent channel of a thread
+    let (channel_id, thread_id) = match msg.channel_id.to_channel(ctx).await {
+        Ok(Channel::Guild(channel)) if matches!(
+            channel.kind,
+            ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+        ) => match channel.parent_id {
+            Some(parent) => (parent, Some(channel.id)),
+            None => return false,
+        },
+        Ok(_) => (msg.channel_id, None),
+        Err(_) => return false,
+    };
+
+    let mut files = Vec::new();
+    for attachment in &msg.attachments {
+        match attachment.download().await {
+            Ok(data) => files.push(CreateAttachment::bytes(data, attachment.filename.clone())),
+            Err(_) => return false,
+        }
+    }
+
+    let name = msg.member.as_ref()
+        .and_then(|member| member.nick.clone())
+        .or_else(|| msg.author.global_name.clone())
+        .unwrap_or_else(|| msg.author.name.clone());
+    let mut builder = ExecuteWebhook::new()
+        .content(content)
+        .username(name)
+        .avatar_url(msg.author.face())
+        // The original message already pinged everyone it mentions
+        .allowed_mentions(CreateAllowedMentions::new())
+        .add_files(files);
+    if let Some(thread_id) = thread_id {
+        builder = builder.in_thread(thread_id);
+    }
+
+    // The cached webhook may have been deleted by a moderator; look it up again once
+    for _ in 0..2 {
+        let Some(webhook) = channel_webhook(ctx, channel_id).await else {
+            return false;
+        };
+        if webhook.execute(&ctx.http, false, builder.clone()).await.is_ok() {
+            return true;
+        }
+        WEBHOOKS.lock().unwrap().remove(&channel_id);
+    }
+    false
+}